@@ -1,52 +1,47 @@
 use anyhow::Result;
 
-mod openai_openapi {
-  use crate::Result;
-  use regex::Regex;
-  use std::{env, fs::{File, self}, path::Path};
-  use serde::Deserialize;
+#[allow(dead_code)]
+#[path = "src/openapi/catalog.rs"]
+mod catalog;
 
-  #[derive(Deserialize)]
-  struct OpenAIManifest {
-    paths: serde_yaml::Mapping,
-  }
+mod openai_openapi {
+  use crate::{catalog::Catalog, Result};
+  use std::{env, fs::{File, self}, path::{Path, PathBuf}};
 
+  /// Generate the catalog into `OUT_DIR` from the OpenAPI specification in the git submodule,
+  /// or copy the prebuilt catalog if the submodule is not checked out.
+  ///
+  /// The prebuilt catalog is updated if the environment variable
+  /// `OPENAI_CLIENT_UPDATE_PREBUILT_CATALOG` is `1`.
   pub fn build() -> Result<()>
   {
-    if env::var("DOCS_RS").is_ok_and(|v| v == "1") {
-      println!("cargo:warning=Skipping openai_openapi::build for docs.rs");
-      return Ok(());
-    }
     let manifest_file = "external/openai-openapi/openapi.yaml";
-    let asset_file = "assets/openai-openapi-paths-regex";
+    let prebuilt_catalog_file = "assets/openai-openapi-catalog.json";
+    let catalog_file = PathBuf::from(env::var("OUT_DIR")?).join("openai-openapi-catalog.json");
     println!("cargo:rerun-if-changed={manifest_file}");
+    println!("cargo:rerun-if-changed={prebuilt_catalog_file}");
+    println!("cargo:rerun-if-changed=src/openapi/catalog.rs");
+    println!("cargo:rerun-if-env-changed=OPENAI_CLIENT_UPDATE_PREBUILT_CATALOG");
 
-    let paths = fetch_paths(manifest_file)?;
-    let regex = convert_paths_to_regex(&paths)?;
-    fs::write(asset_file, regex)?;
+    if env::var("DOCS_RS").is_ok_and(|v| v == "1") || !Path::new(manifest_file).is_file() {
+      println!("cargo:warning=Using the prebuilt catalog for not finding {manifest_file}");
+      fs::copy(prebuilt_catalog_file, catalog_file)?;
+      return Ok(());
+    }
+    let catalog = serde_json::to_string(&fetch_catalog(manifest_file)?)?;
+    fs::write(catalog_file, &catalog)?;
+    if env::var("OPENAI_CLIENT_UPDATE_PREBUILT_CATALOG").is_ok_and(|v| v == "1") {
+      fs::write(prebuilt_catalog_file, &catalog)?;
+    }
     Ok(())
   }
 
-  fn fetch_paths<P>(path: P) -> Result<Vec<String>>
+  fn fetch_catalog<P>(path: P) -> Result<Catalog>
   where
     P: AsRef<Path>,
   {
-    Ok(serde_yaml::from_reader::<File, OpenAIManifest>(File::open(path)?)?
-      .paths
-      .keys()
-      .filter_map(serde_yaml::Value::as_str)
-      .map(|s| s[1..].to_string())
-      .collect()
-    )
-  }
-
-  fn convert_paths_to_regex(paths: &[String]) -> Result<String> {
-    let padded_str = format!("(({}$))", paths.join("$)|("));
-    let fslash_escaped_str = Regex::new(r"/")?
-      .replace_all(&padded_str, r"\/");
-    let wildcard_transformed_str = Regex::new(r"\{.*?\}")?
-      .replace_all(&fslash_escaped_str, r".*");
-    Ok(wildcard_transformed_str.to_string())
+    let document: serde_json::Value = serde_yaml::from_reader(File::open(path)?)?;
+    Catalog::from_openapi(&document)
   }
 }
 
@@ -3,6 +3,10 @@
 #![warn(missing_docs)]
 #![cfg_attr(docsrs, feature(doc_cfg))]
 
+/// The OpenAPI specification.
+pub mod openapi;
+pub use openapi::*;
+
 /// The CLI program.
 pub mod program;
 pub use program::*;
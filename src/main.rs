@@ -1,11 +1,19 @@
 use openai_client_cli::entry::*;
 use std::{process::exit, env::args_os};
-use tracing::error;
+use tracing::{dispatcher, error};
 
 #[tokio::main(flavor = "current_thread")]
 async fn main() {
-  if let Err(err) = Entry::parse_from(args_os()).run().await {
-    error!("{:?}", err);
+  let entry = Entry::parse_from(args_os());
+  let redactor = entry.redactor();
+  let log_file = entry.log_file.is_some();
+  if let Err(err) = entry.run_cli().await {
+    let message = redactor.redact(&format!("{:?}", err)).into_owned();
+    eprintln!("Error: {}", message);
+    // The log file also records the error, which is written only to the file without being teed to the standard error.
+    if log_file && dispatcher::has_been_set() {
+      error!("{}", message);
+    }
     exit(1);
   }
 }
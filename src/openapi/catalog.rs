@@ -0,0 +1,301 @@
+use anyhow::{Error, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// The HTTP methods of operations in the OpenAPI specification.
+pub const METHODS: [&str; 8] = ["get", "put", "post", "delete", "options", "head", "patch", "trace"];
+
+/// The keywords of schemas kept in the catalog, the others are dropped to keep it compact.
+pub const SCHEMA_KEYWORDS: [&str; 22] = [
+  "additionalProperties", "allOf", "anyOf", "default", "deprecated", "description",
+  "enum", "example", "exclusiveMinimum", "format", "items", "maxItems",
+  "maxLength", "maximum", "minItems", "minLength", "minimum", "nullable",
+  "oneOf", "properties", "required", "type",
+];
+
+/// The catalog of the API endpoints in the OpenAPI specification.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct Catalog {
+  /// The endpoints.
+  pub endpoints: Vec<Endpoint>,
+}
+
+/// The API endpoint, which is a path template with its operations.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct Endpoint {
+  /// The path template without the leading slash, for example, `files/{file_id}`.
+  pub path: String,
+
+  /// The operations.
+  pub operations: Vec<Operation>,
+}
+
+/// The API operation, which is an HTTP method on an endpoint.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct Operation {
+  /// Whether it is deprecated by the flag `deprecated` of the operation or its group.
+  #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+  pub deprecated: bool,
+
+  /// Whether it is legacy by the flag `legacy` of its group.
+  #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+  pub legacy: bool,
+
+  /// The HTTP method in uppercase, for example, `GET`.
+  pub method: String,
+
+  /// The operation ID, for example, `createChatCompletion`.
+  pub operation_id: String,
+
+  /// The request body.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub request: Option<RequestBody>,
+
+  /// The successful response body in JSON.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub response: Option<ResponseBody>,
+
+  /// The first line of the summary,
+  /// for example, `Creates a model response for the given chat conversation.`
+  #[serde(default, skip_serializing_if = "String::is_empty")]
+  pub summary: String,
+}
+
+/// The request body of an API operation.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct RequestBody {
+  /// The content type, for example, `application/json` or `multipart/form-data`.
+  pub content_type: String,
+
+  /// The example body taken from the `curl` example of the operation.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub example: Option<Value>,
+
+  /// The schema, in which the references are inlined.
+  pub schema: Value,
+}
+
+/// The successful response body of an API operation in JSON.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct ResponseBody {
+  /// The schema, in which the references are inlined.
+  pub schema: Value,
+
+  /// The schema of each data in the server-sent events if it differs from [`ResponseBody::schema`].
+  ///
+  /// It is taken from the schema named with the suffix `StreamResponse` instead of `Response`,
+  /// for example, `CreateChatCompletionStreamResponse`.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub stream_schema: Option<Value>,
+}
+
+impl Catalog {
+  /// Create a catalog from the OpenAPI document.
+  pub fn from_openapi(document: &Value) -> Result<Self> {
+    let paths = document
+      .get("paths")
+      .and_then(Value::as_object)
+      .ok_or(Error::msg("The OpenAPI document does not contain the field `paths`"))?;
+    let groups = document
+      .pointer("/x-oaiMeta/groups")
+      .and_then(Value::as_array)
+      .map(Vec::as_slice)
+      .unwrap_or_default();
+    let group_flag = |operation_id: &str, flag: &str| groups.iter().any(|group| {
+      group.get(flag).and_then(Value::as_bool) == Some(true)
+        && group
+          .get("sections")
+          .and_then(Value::as_array)
+          .into_iter()
+          .flatten()
+          .any(|section| section.get("key").and_then(Value::as_str) == Some(operation_id))
+    });
+    let endpoints = paths
+      .iter()
+      .map(|(path, item)| Endpoint {
+        path: path.trim_start_matches('/').to_string(),
+        operations: METHODS
+          .iter()
+          .filter_map(|method| {
+            let operation = item.get(method)?;
+            let text = |key: &str| operation
+              .get(key)
+              .and_then(Value::as_str)
+              .unwrap_or_default()
+              .to_string();
+            let operation_id = text("operationId");
+            Some(Operation {
+              deprecated: operation.get("deprecated").and_then(Value::as_bool) == Some(true)
+                || group_flag(&operation_id, "deprecated"),
+              legacy: group_flag(&operation_id, "legacy"),
+              method: method.to_uppercase(),
+              operation_id,
+              request: RequestBody::from_openapi(document, operation),
+              response: ResponseBody::from_openapi(document, operation),
+              summary: text("summary").lines().next().unwrap_or_default().trim().to_string(),
+            })
+          })
+          .collect(),
+      })
+      .collect();
+    Ok(Self { endpoints })
+  }
+
+  /// Returns the endpoint of the path template.
+  pub fn endpoint(&self, template: &str) -> Option<&Endpoint> {
+    self.endpoints
+      .iter()
+      .find(|endpoint| endpoint.path == template)
+  }
+}
+
+impl Endpoint {
+  /// Returns the operation of the HTTP method.
+  pub fn operation(&self, method: &str) -> Option<&Operation> {
+    self.operations
+      .iter()
+      .find(|operation| operation.method.eq_ignore_ascii_case(method))
+  }
+
+  /// Returns whether all the operations are deprecated.
+  pub fn is_deprecated(&self) -> bool {
+    !self.operations.is_empty() && self.operations.iter().all(|operation| operation.deprecated)
+  }
+
+  /// Returns whether all the operations are legacy.
+  pub fn is_legacy(&self) -> bool {
+    !self.operations.is_empty() && self.operations.iter().all(|operation| operation.legacy)
+  }
+
+  /// Returns the HTTP methods of the operations.
+  pub fn methods(&self) -> Vec<&str> {
+    self.operations
+      .iter()
+      .map(|operation| operation.method.as_str())
+      .collect()
+  }
+}
+
+impl RequestBody {
+  /// Create a request body from the operation in the OpenAPI document.
+  ///
+  /// The first content type is taken if there are many.
+  pub fn from_openapi(document: &Value, operation: &Value) -> Option<Self> {
+    let (content_type, media) = operation
+      .pointer("/requestBody/content")?
+      .as_object()?
+      .iter()
+      .next()?;
+    let schema = compact_schema(document, media.get("schema")?, &mut vec![]);
+    let mut example = operation
+      .pointer("/x-oaiMeta/examples")
+      .map(|examples| examples.get(0).unwrap_or(examples))
+      .and_then(|example| example.pointer("/request/curl"))
+      .and_then(Value::as_str)
+      .and_then(curl_body);
+    // The placeholders like `VAR_model_id` are replaced with the examples
+    // or the first enum values of the fields
+    for (name, value) in example.iter_mut().filter_map(Value::as_object_mut).flatten() {
+      if !value.as_str().is_some_and(|text| text.starts_with("VAR_")) {
+        continue;
+      }
+      let Some(field) = schema.pointer(&format!("/properties/{name}")) else {
+        continue;
+      };
+      let variants = field.get("anyOf").and_then(Value::as_array).into_iter().flatten();
+      if let Some(field_example) = field.get("example").or_else(|| {
+        std::iter::once(field)
+          .chain(variants)
+          .find_map(|schema| schema.pointer("/enum/0"))
+      }) {
+        *value = field_example.clone();
+      }
+    }
+    Some(Self {
+      content_type: content_type.clone(),
+      example,
+      schema,
+    })
+  }
+}
+
+impl ResponseBody {
+  /// Create a response body from the successful response of the operation in the OpenAPI document.
+  pub fn from_openapi(document: &Value, operation: &Value) -> Option<Self> {
+    let schema = operation.pointer("/responses/200/content/application~1json/schema")?;
+    let stream_schema = schema
+      .get("$ref")
+      .and_then(Value::as_str)
+      .and_then(|reference| reference.strip_suffix("Response"))
+      .map(|reference| Value::from_iter([("$ref", format!("{reference}StreamResponse"))]))
+      .filter(|reference| {
+        reference["$ref"]
+          .as_str()
+          .and_then(|reference| reference.strip_prefix('#'))
+          .is_some_and(|pointer| document.pointer(pointer).is_some())
+      });
+    Some(Self {
+      schema: compact_schema(document, schema, &mut vec![]),
+      stream_schema: stream_schema.map(|schema| compact_schema(document, &schema, &mut vec![])),
+    })
+  }
+}
+
+/// Returns the JSON body in the `-d '...'` option of a `curl` command.
+fn curl_body(command: &str) -> Option<Value> {
+  let (_, body) = command.split_once("-d '")?;
+  let (body, _) = body.rsplit_once('\'')?;
+  serde_json::from_str(body).ok()
+}
+
+/// Returns the schema with the references inlined and only the keywords in [`SCHEMA_KEYWORDS`].
+///
+/// The recursive references are replaced with empty schemas.
+/// Only the first line of descriptions is kept.
+fn compact_schema(document: &Value, schema: &Value, refs: &mut Vec<String>) -> Value {
+  let Some(object) = schema.as_object() else {
+    return schema.clone();
+  };
+  if let Some(reference) = object.get("$ref").and_then(Value::as_str) {
+    if refs.iter().any(|r| r == reference) {
+      return Value::Object(Default::default());
+    }
+    let target = reference
+      .strip_prefix('#')
+      .and_then(|pointer| document.pointer(pointer))
+      .cloned()
+      .unwrap_or_default();
+    refs.push(reference.to_string());
+    let schema = compact_schema(document, &target, refs);
+    refs.pop();
+    return schema;
+  }
+  Value::Object(
+    object
+      .iter()
+      .filter(|(key, _)| SCHEMA_KEYWORDS.contains(&key.as_str()))
+      .map(|(key, value)| {
+        let value = match (key.as_str(), value) {
+          ("properties", Value::Object(properties)) => Value::Object(
+            properties
+              .iter()
+              .map(|(name, schema)| (name.clone(), compact_schema(document, schema, refs)))
+              .collect()
+          ),
+          ("allOf" | "anyOf" | "oneOf", Value::Array(schemas)) => Value::Array(
+            schemas
+              .iter()
+              .map(|schema| compact_schema(document, schema, refs))
+              .collect()
+          ),
+          ("additionalProperties" | "items", schema) => compact_schema(document, schema, refs),
+          ("description", Value::String(text)) => {
+            Value::String(text.lines().next().unwrap_or_default().trim().to_string())
+          },
+          (_, value) => value.clone(),
+        };
+        (key.clone(), value)
+      })
+      .collect()
+  )
+}
@@ -0,0 +1,98 @@
+/// The deprecated path templates and their replacements.
+///
+/// Some of them have been removed from the OpenAPI specification.
+pub const DEPRECATED_PATHS: [(&str, &str); 10] = [
+  ("edits", "chat/completions"),
+  ("engines", "models"),
+  ("engines/{engine_id}", "models/{model}"),
+  ("engines/{engine_id}/completions", "completions"),
+  ("engines/{engine_id}/embeddings", "embeddings"),
+  ("engines/{engine_id}/search", "embeddings"),
+  ("fine-tunes", "fine_tuning/jobs"),
+  ("fine-tunes/{fine_tune_id}", "fine_tuning/jobs/{fine_tuning_job_id}"),
+  ("fine-tunes/{fine_tune_id}/cancel", "fine_tuning/jobs/{fine_tuning_job_id}/cancel"),
+  ("fine-tunes/{fine_tune_id}/events", "fine_tuning/jobs/{fine_tuning_job_id}/events"),
+];
+
+/// The legacy path templates, which are still supported, and their replacements.
+pub const LEGACY_PATHS: [(&str, &str); 1] = [
+  ("completions", "chat/completions"),
+];
+
+/// The deprecated model.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct DeprecatedModel {
+  /// The model ID, for example, `text-davinci-003`.
+  pub name: &'static str,
+
+  /// The recommended replacement model ID.
+  pub replacement: &'static str,
+
+  /// The shutdown date in the format of `YYYY-MM-DD`.
+  pub shutdown: &'static str,
+}
+
+/// The deprecated models with their shutdown dates and replacements,
+/// which are maintained after the deprecations page of the OpenAI documentation.
+pub const DEPRECATED_MODELS: [DeprecatedModel; 33] = [
+  model("ada", "2024-01-04", "babbage-002"),
+  model("babbage", "2024-01-04", "babbage-002"),
+  model("code-davinci-edit-001", "2024-01-04", "gpt-4"),
+  model("code-search-ada-code-001", "2024-01-04", "text-embedding-ada-002"),
+  model("code-search-ada-text-001", "2024-01-04", "text-embedding-ada-002"),
+  model("code-search-babbage-code-001", "2024-01-04", "text-embedding-ada-002"),
+  model("code-search-babbage-text-001", "2024-01-04", "text-embedding-ada-002"),
+  model("curie", "2024-01-04", "davinci-002"),
+  model("davinci", "2024-01-04", "davinci-002"),
+  model("gpt-3.5-turbo-0301", "2024-09-13", "gpt-3.5-turbo"),
+  model("gpt-3.5-turbo-0613", "2024-09-13", "gpt-3.5-turbo"),
+  model("gpt-3.5-turbo-16k-0613", "2024-09-13", "gpt-3.5-turbo"),
+  model("gpt-4-0314", "2024-06-13", "gpt-4-0613"),
+  model("gpt-4-32k", "2025-06-06", "gpt-4o"),
+  model("gpt-4-32k-0314", "2024-06-13", "gpt-4o"),
+  model("gpt-4-32k-0613", "2025-06-06", "gpt-4o"),
+  model("gpt-4-vision-preview", "2024-12-06", "gpt-4o"),
+  model("gpt-4.5-preview", "2025-07-14", "gpt-4.1"),
+  model("text-ada-001", "2024-01-04", "gpt-3.5-turbo-instruct"),
+  model("text-babbage-001", "2024-01-04", "gpt-3.5-turbo-instruct"),
+  model("text-curie-001", "2024-01-04", "gpt-3.5-turbo-instruct"),
+  model("text-davinci-001", "2024-01-04", "gpt-3.5-turbo-instruct"),
+  model("text-davinci-002", "2024-01-04", "gpt-3.5-turbo-instruct"),
+  model("text-davinci-003", "2024-01-04", "gpt-3.5-turbo-instruct"),
+  model("text-davinci-edit-001", "2024-01-04", "gpt-4"),
+  model("text-search-ada-doc-001", "2024-01-04", "text-embedding-ada-002"),
+  model("text-search-ada-query-001", "2024-01-04", "text-embedding-ada-002"),
+  model("text-search-babbage-doc-001", "2024-01-04", "text-embedding-ada-002"),
+  model("text-search-curie-doc-001", "2024-01-04", "text-embedding-ada-002"),
+  model("text-search-davinci-doc-001", "2024-01-04", "text-embedding-ada-002"),
+  model("text-similarity-ada-001", "2024-01-04", "text-embedding-ada-002"),
+  model("text-similarity-babbage-001", "2024-01-04", "text-embedding-ada-002"),
+  model("text-similarity-davinci-001", "2024-01-04", "text-embedding-ada-002"),
+];
+
+/// Returns the replacement of the deprecated path template.
+pub fn replacement(template: &str) -> Option<&'static str> {
+  DEPRECATED_PATHS
+    .iter()
+    .find(|(deprecated, _)| *deprecated == template)
+    .map(|(_, replacement)| *replacement)
+}
+
+/// Returns the replacement of the legacy path template.
+pub fn legacy_replacement(template: &str) -> Option<&'static str> {
+  LEGACY_PATHS
+    .iter()
+    .find(|(legacy, _)| *legacy == template)
+    .map(|(_, replacement)| *replacement)
+}
+
+/// Returns the deprecated model of the model ID.
+pub fn deprecated_model(name: &str) -> Option<&'static DeprecatedModel> {
+  DEPRECATED_MODELS
+    .iter()
+    .find(|model| model.name == name)
+}
+
+const fn model(name: &'static str, shutdown: &'static str, replacement: &'static str) -> DeprecatedModel {
+  DeprecatedModel { name, replacement, shutdown }
+}
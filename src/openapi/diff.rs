@@ -0,0 +1,227 @@
+use crate::openapi::*;
+use serde::Serialize;
+use std::{collections::BTreeMap, fmt};
+
+/// The differences of the endpoints between two catalogs.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct CatalogDiff {
+  /// The added path templates.
+  pub added_paths: Vec<String>,
+
+  /// The changed methods on the path templates in both catalogs.
+  pub changed_methods: Vec<MethodChange>,
+
+  /// The newly deprecated operations.
+  pub deprecated_operations: Vec<OperationChange>,
+
+  /// The new required fields of the request bodies.
+  pub new_required_fields: Vec<OperationChange>,
+
+  /// The removed path templates.
+  pub removed_paths: Vec<String>,
+
+  /// The removed fields of the response bodies.
+  pub removed_response_fields: Vec<OperationChange>,
+}
+
+/// The changed methods on a path template.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct MethodChange {
+  /// The path template.
+  pub path: String,
+
+  /// The added methods.
+  pub added: Vec<String>,
+
+  /// The removed methods.
+  pub removed: Vec<String>,
+}
+
+/// The change of an operation, with the changed fields if any.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct OperationChange {
+  /// The HTTP method.
+  pub method: String,
+
+  /// The path template.
+  pub path: String,
+
+  /// The changed fields in the format of `messages[].role`.
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  pub fields: Vec<String>,
+}
+
+impl CatalogDiff {
+  /// Compare the old catalog with the new one.
+  pub fn new(old: &Catalog, new: &Catalog) -> Self {
+    let mut diff = Self::default();
+    for endpoint in &new.endpoints {
+      if old.endpoint(&endpoint.path).is_none() {
+        diff.added_paths.push(endpoint.path.clone());
+      }
+    }
+    for old_endpoint in &old.endpoints {
+      let Some(new_endpoint) = new.endpoint(&old_endpoint.path) else {
+        diff.removed_paths.push(old_endpoint.path.clone());
+        continue;
+      };
+      let path = &new_endpoint.path;
+      let (old_methods, new_methods) = (old_endpoint.methods(), new_endpoint.methods());
+      let change = MethodChange {
+        path: path.clone(),
+        added: difference(&new_methods, &old_methods),
+        removed: difference(&old_methods, &new_methods),
+      };
+      if !change.added.is_empty() || !change.removed.is_empty() {
+        diff.changed_methods.push(change);
+      }
+
+      for new_operation in &new_endpoint.operations {
+        let Some(old_operation) = old_endpoint.operation(&new_operation.method) else {
+          continue;
+        };
+        let change = |fields| OperationChange {
+          method: new_operation.method.clone(),
+          path: path.clone(),
+          fields,
+        };
+        if new_operation.deprecated && !old_operation.deprecated {
+          diff.deprecated_operations.push(change(vec![]));
+        }
+
+        let (old_fields, new_fields) = (
+          request_fields(old_operation),
+          request_fields(new_operation),
+        );
+        let required: Vec<String> = new_fields
+          .iter()
+          .filter(|(name, required)| **required && old_fields.get(*name) != Some(&true))
+          .map(|(name, _)| name.clone())
+          .collect();
+        if !required.is_empty() {
+          diff.new_required_fields.push(change(required));
+        }
+
+        let (old_fields, new_fields) = (
+          response_fields(old_operation),
+          response_fields(new_operation),
+        );
+        let removed: Vec<String> = old_fields
+          .keys()
+          .filter(|name| !new_fields.contains_key(*name))
+          .cloned()
+          .collect();
+        if !removed.is_empty() {
+          diff.removed_response_fields.push(change(removed));
+        }
+      }
+    }
+    diff
+  }
+
+  /// Returns whether there are no differences.
+  pub fn is_empty(&self) -> bool {
+    self.added_paths.is_empty()
+      && self.changed_methods.is_empty()
+      && self.deprecated_operations.is_empty()
+      && self.new_required_fields.is_empty()
+      && self.removed_paths.is_empty()
+      && self.removed_response_fields.is_empty()
+  }
+}
+
+impl fmt::Display for CatalogDiff {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    if self.is_empty() {
+      return writeln!(f, "No changes in the endpoints");
+    }
+    let operations = |changes: &[OperationChange]| -> Vec<String> {
+      changes
+        .iter()
+        .map(|change| {
+          let fields: Vec<String> = change.fields.iter().map(|field| format!("`{field}`")).collect();
+          match fields.is_empty() {
+            true => format!("{} {}", change.method, change.path),
+            false => format!("{} {}: {}", change.method, change.path, fields.join(", ")),
+          }
+        })
+        .collect()
+    };
+    let sections = [
+      ("Added paths", "+", self.added_paths.clone()),
+      ("Removed paths", "-", self.removed_paths.clone()),
+      (
+        "Changed methods",
+        "~",
+        self.changed_methods
+          .iter()
+          .map(|change| {
+            let added = change.added.iter().map(|method| format!("+{method}"));
+            let removed = change.removed.iter().map(|method| format!("-{method}"));
+            format!("{}: {}", change.path, added.chain(removed).collect::<Vec<_>>().join(" "))
+          })
+          .collect(),
+      ),
+      ("New required request fields", "!", operations(&self.new_required_fields)),
+      ("Removed response fields", "-", operations(&self.removed_response_fields)),
+      ("Newly deprecated operations", "!", operations(&self.deprecated_operations)),
+    ];
+    for (heading, sign, lines) in sections {
+      if lines.is_empty() {
+        continue;
+      }
+      writeln!(f, "{heading}:")?;
+      for line in lines {
+        writeln!(f, "  {sign} {line}")?;
+      }
+    }
+    Ok(())
+  }
+}
+
+/// Returns the items in `a` but not in `b`.
+fn difference(a: &[&str], b: &[&str]) -> Vec<String> {
+  a.iter().filter(|item| !b.contains(item)).map(|item| item.to_string()).collect()
+}
+
+/// Returns the fields of the request body with whether they are required.
+fn request_fields(operation: &Operation) -> BTreeMap<String, bool> {
+  let mut fields = BTreeMap::new();
+  if let Some(request) = operation.request.as_ref() {
+    collect_fields(Schema(&request.schema), "", true, &mut fields);
+  }
+  fields
+}
+
+/// Returns the fields of the response body with whether they are required.
+fn response_fields(operation: &Operation) -> BTreeMap<String, bool> {
+  let mut fields = BTreeMap::new();
+  if let Some(response) = operation.response.as_ref() {
+    collect_fields(Schema(&response.schema), "", true, &mut fields);
+  }
+  fields
+}
+
+/// Collect the nested fields in the format of `messages[].role`.
+///
+/// The fields of the variants are merged, and they are required if required in any variant.
+/// The nested fields are required only if all their parents are required.
+fn collect_fields(schema: Schema, prefix: &str, required: bool, fields: &mut BTreeMap<String, bool>) {
+  for keyword in ["allOf", "anyOf", "oneOf"] {
+    for variant in schema.variants(keyword) {
+      collect_fields(variant, prefix, required, fields);
+    }
+  }
+  if let Some(items) = schema.get("items") {
+    collect_fields(Schema(items), &format!("{prefix}[]"), required, fields);
+  }
+  for field in schema.fields() {
+    let name = match prefix.is_empty() {
+      true => field.name.to_string(),
+      false => format!("{prefix}.{}", field.name),
+    };
+    let required = required && field.required;
+    *fields.entry(name.clone()).or_default() |= required;
+    collect_fields(field.schema, &name, required, fields);
+  }
+}
@@ -0,0 +1,14 @@
+use crate::openapi::*;
+use std::sync::OnceLock;
+
+impl Catalog {
+  /// Returns the catalog embedded from the bundled OpenAPI specification,
+  /// which is generated by the build script or prebuilt.
+  pub fn embedded() -> &'static Self {
+    static CATALOG: OnceLock<Catalog> = OnceLock::new();
+    CATALOG.get_or_init(|| {
+      serde_json::from_str(include_str!(concat!(env!("OUT_DIR"), "/openai-openapi-catalog.json")))
+        .expect("The embedded catalog should be valid")
+    })
+  }
+}
@@ -0,0 +1,30 @@
+/// The catalog module.
+pub mod catalog;
+pub use catalog::*;
+
+/// The deprecation module.
+pub mod deprecation;
+pub use deprecation::*;
+
+/// The catalog diff module.
+pub mod diff;
+pub use diff::*;
+
+/// The embedded catalog module.
+pub mod embedded;
+
+/// The path router module.
+pub mod router;
+pub use router::*;
+
+/// The schema module.
+pub mod schema;
+pub use schema::*;
+
+/// The path suggestion module.
+pub mod suggestion;
+pub use suggestion::*;
+
+/// The schema validation module.
+pub mod validation;
+pub use validation::*;
@@ -0,0 +1,187 @@
+use crate::{openapi::*, Error, Result};
+use std::{borrow::Cow, str::FromStr, sync::OnceLock};
+
+/// The segment of a path template.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Segment {
+  /// The literal segment, for example, `files`.
+  Literal(String),
+
+  /// The path parameter, for example, `{file_id}`.
+  Parameter(String),
+}
+
+/// The path template, for example, `files/{file_id}/content`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Template(Vec<Segment>);
+
+/// The route of a path, which is the matched endpoint with the captured path parameters.
+#[derive(Clone, Debug)]
+pub struct Route<'a> {
+  /// The matched endpoint.
+  pub endpoint: &'a Endpoint,
+
+  /// The captured path parameters in the order of the path template.
+  pub params: Vec<(String, String)>,
+}
+
+/// The path router, which matches whole paths against the path templates of endpoints.
+pub struct Router<'a> {
+  catalog: &'a Catalog,
+  templates: Cow<'a, [Template]>,
+}
+
+impl Template {
+  /// Returns the segments.
+  pub fn segments(&self) -> &[Segment] {
+    &self.0
+  }
+
+  /// Returns the names of the path parameters.
+  pub fn params(&self) -> Vec<&str> {
+    self.0
+      .iter()
+      .filter_map(|segment| match segment {
+        Segment::Parameter(name) => Some(name.as_str()),
+        Segment::Literal(_) => None,
+      })
+      .collect()
+  }
+
+  /// Capture the path parameters if the whole path matches the template.
+  pub fn capture(&self, path: &str) -> Option<Vec<(String, String)>> {
+    let segments: Vec<&str> = path.split('/').collect();
+    if segments.len() != self.0.len() {
+      return None;
+    }
+    let mut params = vec![];
+    for (template, segment) in self.0.iter().zip(segments) {
+      match template {
+        Segment::Literal(literal) if literal == segment => {},
+        Segment::Parameter(name) if !segment.is_empty() => {
+          params.push((name.clone(), segment.to_string()));
+        },
+        _ => return None,
+      }
+    }
+    Some(params)
+  }
+
+  /// Substitute the path parameters into the template, which are percent-encoded.
+  ///
+  /// It fails with the names of all the missing parameters.
+  pub fn render(&self, params: &[(String, String)]) -> Result<String> {
+    let mut missing = vec![];
+    let segments: Vec<String> = self.0
+      .iter()
+      .map(|segment| match segment {
+        Segment::Literal(literal) => literal.clone(),
+        Segment::Parameter(name) => params
+          .iter()
+          .find(|(key, _)| key == name)
+          .map(|(_, value)| encode(value))
+          .unwrap_or_else(|| {
+            missing.push(format!("`{name}`"));
+            String::new()
+          }),
+      })
+      .collect();
+    if !missing.is_empty() {
+      return Err(Error::msg(format!(
+        "Missing the path parameters: {}",
+        missing.join(", "),
+      )));
+    }
+    Ok(segments.join("/"))
+  }
+
+  /// Returns the specificity, the literal segments are more specific than the parameters.
+  fn specificity(&self) -> Vec<bool> {
+    self.0
+      .iter()
+      .map(|segment| matches!(segment, Segment::Literal(_)))
+      .collect()
+  }
+}
+
+impl FromStr for Template {
+  type Err = Error;
+
+  fn from_str(template: &str) -> Result<Self> {
+    Ok(Self(
+      template
+        .trim_matches('/')
+        .split('/')
+        .map(|segment| match segment.strip_prefix('{').and_then(|s| s.strip_suffix('}')) {
+          Some(name) => Segment::Parameter(name.to_string()),
+          None => Segment::Literal(segment.to_string()),
+        })
+        .collect()
+    ))
+  }
+}
+
+impl<'a> Router<'a> {
+  /// Create a router from the endpoints in the catalog.
+  pub fn new(catalog: &'a Catalog) -> Self {
+    Self { catalog, templates: Cow::Owned(Self::templates(catalog)) }
+  }
+
+  /// Create a router from the endpoints in the catalog and their path templates,
+  /// which are parsed in advance by [`Router::templates`].
+  pub fn with_templates(catalog: &'a Catalog, templates: &'a [Template]) -> Self {
+    Self { catalog, templates: Cow::Borrowed(templates) }
+  }
+
+  /// Returns the path templates of the endpoints in the catalog in order.
+  pub fn templates(catalog: &Catalog) -> Vec<Template> {
+    catalog.endpoints
+      .iter()
+      // The empty template never matches, which is never the case since the parsing does not fail.
+      .map(|endpoint| Template::from_str(&endpoint.path).unwrap_or(Template(vec![])))
+      .collect()
+  }
+
+  /// Returns the router borrowing the path templates of this one.
+  pub fn borrowed(&self) -> Router<'_> {
+    Router::with_templates(self.catalog, &self.templates)
+  }
+
+  /// Returns the catalog.
+  pub fn catalog(&self) -> &'a Catalog {
+    self.catalog
+  }
+
+  /// Returns the router of the embedded catalog.
+  pub fn embedded() -> &'static Router<'static> {
+    static ROUTER: OnceLock<Router<'static>> = OnceLock::new();
+    ROUTER.get_or_init(|| Router::new(Catalog::embedded()))
+  }
+
+  /// Route the whole path to the matched endpoint.
+  ///
+  /// The literal segments are preferred over the parameters,
+  /// for example, `threads/runs` matches `threads/runs` rather than `threads/{thread_id}`.
+  pub fn route(&self, path: &str) -> Option<Route<'a>> {
+    self.templates
+      .iter()
+      .zip(&self.catalog.endpoints)
+      .filter_map(|(template, endpoint)| {
+        let params = template.capture(path)?;
+        Some((template.specificity(), Route { endpoint, params }))
+      })
+      .max_by(|(a, _), (b, _)| a.cmp(b))
+      .map(|(_, route)| route)
+  }
+}
+
+/// Percent-encode the path parameter, in which only the unreserved characters of RFC 3986 are kept.
+fn encode(value: &str) -> String {
+  value
+    .bytes()
+    .map(|byte| match byte {
+      b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => char::from(byte).to_string(),
+      byte => format!("%{byte:02X}"),
+    })
+    .collect()
+}
@@ -0,0 +1,136 @@
+use serde_json::Value;
+
+/// The schema in the catalog, which is a JSON value with the references inlined.
+#[derive(Clone, Copy, Debug)]
+pub struct Schema<'a>(pub &'a Value);
+
+/// The field of an object schema.
+#[derive(Clone, Copy, Debug)]
+pub struct Field<'a> {
+  /// The name.
+  pub name: &'a str,
+
+  /// Whether it is required.
+  pub required: bool,
+
+  /// The schema.
+  pub schema: Schema<'a>,
+}
+
+impl<'a> Schema<'a> {
+  /// Returns the value of the keyword.
+  pub fn get(&self, keyword: &str) -> Option<&'a Value> {
+    self.0.get(keyword)
+  }
+
+  /// Returns the first line of the description.
+  pub fn description(&self) -> Option<&'a str> {
+    self.get("description").and_then(Value::as_str).filter(|text| !text.is_empty())
+  }
+
+  /// Returns the default value.
+  pub fn default_value(&self) -> Option<&'a Value> {
+    self.get("default")
+  }
+
+  /// Returns the fields of the object schema in the order of the specification.
+  pub fn fields(&self) -> Vec<Field<'a>> {
+    let required: Vec<&str> = self
+      .get("required")
+      .and_then(Value::as_array)
+      .map(|names| names.iter().filter_map(Value::as_str).collect())
+      .unwrap_or_default();
+    self
+      .get("properties")
+      .and_then(Value::as_object)
+      .map(|properties| {
+        properties
+          .iter()
+          .map(|(name, schema)| Field {
+            name,
+            required: required.contains(&name.as_str()),
+            schema: Schema(schema),
+          })
+          .collect()
+      })
+      .unwrap_or_default()
+  }
+
+  /// Returns the subschemas of the keyword `allOf`, `anyOf` or `oneOf`.
+  pub fn variants(&self, keyword: &str) -> Vec<Schema<'a>> {
+    self
+      .get(keyword)
+      .and_then(Value::as_array)
+      .map(|schemas| schemas.iter().map(Schema).collect())
+      .unwrap_or_default()
+  }
+
+  /// Returns the placeholder value, which is the default value, the first enum value
+  /// or the zero value of the type. The required fields of objects are filled in.
+  pub fn placeholder(&self) -> Value {
+    if let Some(value) = self.default_value().filter(|value| !value.is_null()) {
+      return value.clone();
+    }
+    if let Some(value) = self.get("enum").and_then(|values| values.get(0)) {
+      return value.clone();
+    }
+    if let Some(variant) = ["anyOf", "oneOf", "allOf"]
+      .into_iter()
+      .find_map(|keyword| self.variants(keyword).into_iter().next())
+    {
+      return variant.placeholder();
+    }
+    match self.get("type").and_then(Value::as_str) {
+      Some("array") => Value::Array(vec![]),
+      Some("boolean") => Value::Bool(false),
+      Some("integer" | "number") => Value::from(0),
+      Some("string") => Value::String(String::new()),
+      _ => Value::Object(
+        self
+          .fields()
+          .into_iter()
+          .filter(|field| field.required)
+          .map(|field| (field.name.to_string(), field.schema.placeholder()))
+          .collect()
+      ),
+    }
+  }
+
+  /// Returns the readable type name,
+  /// for example, `string | null`, `array<object>` or `"auto" | "none"`.
+  pub fn type_name(&self) -> String {
+    let mut names = vec![];
+    if let Some(values) = self.get("enum").and_then(Value::as_array) {
+      names.extend(values.iter().map(Value::to_string));
+    } else if let Some(keyword) = ["anyOf", "oneOf"].into_iter().find(|k| self.get(k).is_some()) {
+      for variant in self.variants(keyword) {
+        names.push(variant.type_name());
+      }
+    } else if self.get("allOf").is_some() {
+      let variants: Vec<String> = self.variants("allOf").iter().map(Schema::type_name).collect();
+      names.push(variants.join(" & "));
+    } else {
+      names.push(match self.get("type").and_then(Value::as_str) {
+        Some("array") => {
+          let items = self.get("items").map_or("any".to_string(), |items| Schema(items).type_name());
+          if items.contains(' ') {
+            format!("array<({items})>")
+          } else {
+            format!("array<{items}>")
+          }
+        },
+        Some("string") if self.get("format").and_then(Value::as_str) == Some("binary") => {
+          "file".to_string()
+        },
+        Some(name) => name.to_string(),
+        None if self.get("properties").is_some() => "object".to_string(),
+        None => "any".to_string(),
+      });
+    }
+    if self.get("nullable").and_then(Value::as_bool) == Some(true) {
+      names.push("null".to_string());
+    }
+    names.dedup();
+    names.join(" | ")
+  }
+}
@@ -0,0 +1,87 @@
+use crate::openapi::*;
+use std::{fmt, str::FromStr};
+
+/// The suggested path template for an unknown path.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Suggestion {
+  /// The suggested path template.
+  pub path: String,
+
+  /// The deprecated path template which is replaced by the suggested one.
+  pub replaces: Option<String>,
+}
+
+impl fmt::Display for Suggestion {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "`{}`", self.path)?;
+    if let Some(replaces) = self.replaces.as_ref() {
+      write!(f, " (replacing the deprecated `{replaces}`)")?;
+    }
+    Ok(())
+  }
+}
+
+impl Catalog {
+  /// Suggest the closest path templates to the unknown path by edit distance.
+  ///
+  /// At most 3 suggestions are returned and they are not farther than the closest one by 1.
+  /// The deprecated path templates are suggested with their replacements.
+  pub fn suggest(&self, path: &str) -> Vec<Suggestion> {
+    let threshold = (path.len() / 3).max(2);
+    let mut candidates: Vec<(usize, &str)> = self.endpoints
+      .iter()
+      .map(|endpoint| endpoint.path.as_str())
+      .chain(DEPRECATED_PATHS.iter().map(|(deprecated, _)| *deprecated))
+      .map(|template| (distance(path, template), template))
+      .filter(|(distance, _)| *distance <= threshold)
+      .collect();
+    candidates.sort();
+    if let Some((best, _)) = candidates.first().copied() {
+      candidates.retain(|(distance, _)| *distance <= best + 1);
+    }
+
+    let mut suggestions: Vec<Suggestion> = vec![];
+    for (_, template) in candidates {
+      let suggestion = match replacement(template) {
+        Some(replacement) => Suggestion {
+          path: replacement.to_string(),
+          replaces: Some(template.to_string()),
+        },
+        None => Suggestion {
+          path: template.to_string(),
+          replaces: None,
+        },
+      };
+      if suggestions.iter().all(|s| s.path != suggestion.path) {
+        suggestions.push(suggestion);
+      }
+    }
+    suggestions.truncate(3);
+    suggestions
+  }
+}
+
+/// Returns the edit distance between the path and the path template.
+///
+/// If they have the same number of segments, the distance is the sum of
+/// the edit distances of literal segments, since any non-empty segment matches a path parameter.
+/// Otherwise, it is the edit distance between the whole strings.
+pub fn distance(path: &str, template: &str) -> usize {
+  let path = path.trim_matches('/');
+  let segments: Vec<&str> = path.split('/').collect();
+  let Ok(parsed) = Template::from_str(template) else {
+    return strsim::levenshtein(path, template);
+  };
+  if segments.len() != parsed.segments().len() {
+    return strsim::levenshtein(path, template);
+  }
+  parsed
+    .segments()
+    .iter()
+    .zip(segments)
+    .map(|(expected, segment)| match expected {
+      Segment::Literal(literal) => strsim::levenshtein(segment, literal),
+      Segment::Parameter(_) => usize::from(segment.is_empty()),
+    })
+    .sum()
+}
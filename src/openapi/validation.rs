@@ -0,0 +1,218 @@
+use crate::openapi::*;
+use serde_json::Value;
+use std::fmt;
+
+/// The violation of a value against a schema.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Violation {
+  /// The JSON pointer to the violating value, for example, `/messages/0/role`.
+  pub pointer: String,
+
+  /// The message, for example, `expected number, found string`.
+  pub message: String,
+}
+
+impl fmt::Display for Violation {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let pointer = if self.pointer.is_empty() { "/" } else { &self.pointer };
+    write!(f, "`{}`: {}", pointer, self.message)
+  }
+}
+
+impl<'a> Schema<'a> {
+  /// Validate the value against the schema and returns all the violations.
+  ///
+  /// The fields not in the properties are unknown unless `additionalProperties` is provided.
+  /// The empty schemas, which are the replaced recursive references, accept any values.
+  pub fn validate(&self, value: &Value) -> Vec<Violation> {
+    let mut violations = vec![];
+    self.validate_at(value, "", &mut violations);
+    violations
+  }
+
+  fn validate_at(&self, value: &Value, pointer: &str, violations: &mut Vec<Violation>) {
+    let violation = |message: String| Violation {
+      pointer: pointer.to_string(),
+      message,
+    };
+    if value.is_null() && self.get("nullable").and_then(Value::as_bool) == Some(true) {
+      return;
+    }
+    if let Some(values) = self.get("enum").and_then(Value::as_array) {
+      if !values.contains(value) {
+        violations.push(violation(format!("expected {}, found {value}", self.type_name())));
+      }
+      return;
+    }
+    if let Some(keyword) = ["anyOf", "oneOf"].into_iter().find(|k| self.get(k).is_some()) {
+      let mut variants: Vec<Schema> = self
+        .variants(keyword)
+        .into_iter()
+        .filter(|variant| variant.accepts_kind(value))
+        .collect();
+      if let Some((name, discriminated)) = discriminate(&variants, value) {
+        if discriminated.is_empty() {
+          let expected: Vec<String> = variants
+            .iter()
+            .filter_map(|variant| variant.0.pointer(&format!("/properties/{name}/enum")))
+            .filter_map(Value::as_array)
+            .flatten()
+            .map(Value::to_string)
+            .collect();
+          violations.push(Violation {
+            pointer: format!("{pointer}/{name}"),
+            message: format!("expected {}, found {}", expected.join(" | "), value[name]),
+          });
+          return;
+        }
+        variants = discriminated;
+      }
+      let candidates: Vec<Vec<Violation>> = variants
+        .iter()
+        .map(|variant| {
+          let mut violations = vec![];
+          variant.validate_at(value, pointer, &mut violations);
+          violations
+        })
+        .collect();
+      match candidates.into_iter().min_by_key(Vec::len) {
+        Some(candidate) => violations.extend(candidate),
+        None => violations.push(violation(format!("expected {}, found {}", self.type_name(), kind(value)))),
+      }
+      return;
+    }
+    for variant in self.variants("allOf") {
+      variant.validate_at(value, pointer, violations);
+    }
+    if !self.accepts_kind(value) {
+      violations.push(violation(format!("expected {}, found {}", self.type_name(), kind(value))));
+      return;
+    }
+
+    match value {
+      Value::Number(number) => {
+        let number = number.as_f64().unwrap_or_default();
+        if let Some(minimum) = self.get("minimum").and_then(Value::as_f64).filter(|m| number < *m) {
+          violations.push(violation(format!("expected a number not less than {minimum}, found {number}")));
+        }
+        if let Some(maximum) = self.get("maximum").and_then(Value::as_f64).filter(|m| number > *m) {
+          violations.push(violation(format!("expected a number not greater than {maximum}, found {number}")));
+        }
+      },
+      Value::Array(items) => {
+        if let Some(min) = self.get("minItems").and_then(Value::as_u64).filter(|m| (items.len() as u64) < *m) {
+          violations.push(violation(format!("expected at least {min} items, found {}", items.len())));
+        }
+        if let Some(max) = self.get("maxItems").and_then(Value::as_u64).filter(|m| (items.len() as u64) > *m) {
+          violations.push(violation(format!("expected at most {max} items, found {}", items.len())));
+        }
+        if let Some(schema) = self.get("items") {
+          for (index, item) in items.iter().enumerate() {
+            Schema(schema).validate_at(item, &format!("{pointer}/{index}"), violations);
+          }
+        }
+      },
+      Value::Object(object) => {
+        let fields = self.fields();
+        for field in fields.iter().filter(|field| field.required) {
+          if !object.contains_key(field.name) {
+            violations.push(violation(format!("missing the required field `{}`", field.name)));
+          }
+        }
+        let additional = self.get("additionalProperties");
+        for (name, value) in object {
+          let child = format!("{pointer}/{}", name.replace('~', "~0").replace('/', "~1"));
+          match (fields.iter().find(|field| field.name == name), additional) {
+            (Some(field), _) => field.schema.validate_at(value, &child, violations),
+            (None, Some(Value::Object(schema))) if !schema.is_empty() => {
+              Schema(additional.unwrap()).validate_at(value, &child, violations);
+            },
+            (None, None | Some(Value::Bool(false))) if !fields.is_empty() => {
+              let suggestion = fields
+                .iter()
+                .map(|field| (strsim::levenshtein(field.name, name), field.name))
+                .filter(|(distance, _)| *distance <= name.len().max(2) / 3 + 1)
+                .min()
+                .map(|(_, name)| format!(", did you mean `{name}`?"))
+                .unwrap_or_default();
+              violations.push(Violation {
+                pointer: child,
+                message: format!("unknown field{suggestion}"),
+              });
+            },
+            _ => {},
+          }
+        }
+      },
+      _ => {},
+    }
+  }
+
+  /// Returns whether the kind of the value is accepted by the type of the schema.
+  fn accepts_kind(&self, value: &Value) -> bool {
+    if value.is_null() && self.get("nullable").and_then(Value::as_bool) == Some(true) {
+      return true;
+    }
+    if let Some(values) = self.get("enum").and_then(Value::as_array) {
+      return values.iter().any(|v| kind(v) == kind(value) || v.is_number() && value.is_number());
+    }
+    if let Some(keyword) = ["anyOf", "oneOf"].into_iter().find(|k| self.get(k).is_some()) {
+      return self.variants(keyword).iter().any(|variant| variant.accepts_kind(value));
+    }
+    match (self.get("type").and_then(Value::as_str), value) {
+      (Some("array"), Value::Array(_))
+      | (Some("boolean"), Value::Bool(_))
+      | (Some("number"), Value::Number(_))
+      | (Some("object"), Value::Object(_))
+      | (Some("string"), Value::String(_))
+      | (Some("null"), Value::Null) => true,
+      (Some("integer"), Value::Number(number)) => {
+        number.is_i64() || number.is_u64() || number.as_f64().is_some_and(|n| n.fract() == 0.0)
+      },
+      (Some(_), _) => false,
+      (None, Value::Object(_)) => true,
+      (None, _) => self.get("properties").is_none(),
+    }
+  }
+}
+
+/// Returns the discriminator name of the object variants and the variants matching its value.
+///
+/// The discriminator is the field in every variant with enum values, for example, `role` of messages.
+fn discriminate<'a, 'b>(variants: &[Schema<'a>], value: &'b Value) -> Option<(&'b str, Vec<Schema<'a>>)> {
+  let object = value.as_object()?;
+  if variants.len() < 2 {
+    return None;
+  }
+  let (name, discriminator) = object.iter().find(|(name, _)| {
+    variants
+      .iter()
+      .all(|variant| variant.0.pointer(&format!("/properties/{name}/enum")).is_some())
+  })?;
+  Some((
+    name,
+    variants
+      .iter()
+      .filter(|variant| {
+        variant.0
+          .pointer(&format!("/properties/{name}/enum"))
+          .and_then(Value::as_array)
+          .is_some_and(|values| values.contains(discriminator))
+      })
+      .copied()
+      .collect(),
+  ))
+}
+
+/// Returns the kind of the value, for example, `string`.
+fn kind(value: &Value) -> &'static str {
+  match value {
+    Value::Null => "null",
+    Value::Bool(_) => "boolean",
+    Value::Number(number) if number.is_f64() => "number",
+    Value::Number(_) => "integer",
+    Value::String(_) => "string",
+    Value::Array(_) => "array",
+    Value::Object(_) => "object",
+  }
+}
@@ -0,0 +1,419 @@
+use crate::{
+  Catalog, CatalogDiff, Context, Endpoint, Error, Operation, Parameter, ParameterItem, Path, Result, Schema, Spec,
+  Template,
+  traits::*,
+};
+use clap::{arg, Subcommand, ValueEnum};
+use serde_json::{Map, Value};
+use std::{io::Write, path::{self, PathBuf}, str::FromStr};
+use tracing::info;
+
+/// The subcommands, which are run instead of sending the API request.
+#[derive(Subcommand)]
+pub enum Command {
+  /// Work with the configuration of the API request.
+  Config {
+    /// The subcommand of the configuration.
+    #[command(subcommand)]
+    command: ConfigCommand,
+  },
+
+  /// Describe the API operations on an endpoint.
+  #[command(
+    long_about = "\
+Describe the API operations on an endpoint in the OpenAPI specification,
+including the summary, the required and optional fields of the request body
+with their types and enums, and an example body.",
+  )]
+  Describe {
+    /// The API request path or the path template.
+    #[arg(
+      help = "\
+The API request path or the path template,
+for example, `chat/completions`, `files/file-abc` or `files/{file_id}`.",
+      value_name = "PATH",
+    )]
+    path: String,
+  },
+
+  /// List the API operations.
+  #[command(
+    long_about = "\
+List the API operations in the OpenAPI specification with their methods, paths and summaries.",
+  )]
+  Endpoints {
+    /// The filter of the API operations.
+    #[arg(
+      help = "\
+The case-insensitive filter of the API operations,
+which matches the methods, the paths, the operation IDs and the summaries.",
+      value_name = "FILTER",
+    )]
+    filter: Option<String>,
+  },
+
+  /// Create a parameter file for an endpoint.
+  #[command(
+    long_about = "\
+Create a parameter file for an endpoint from the request schema and the example
+in the OpenAPI specification. The required fields are filled in and the optional fields are listed.
+The file is created at <PARAM_FILE_PATH> if it is provided,
+otherwise at `openai.json`, which is the first default path of the parameter file.",
+  )]
+  Init {
+    /// Switch for overwriting the existing parameter file.
+    #[arg(
+      default_value = "false",
+      help = "\
+Switch for overwriting the existing parameter file.
+It is disabled by default.",
+      long,
+      short = 'f',
+    )]
+    force: bool,
+
+    /// The API request path or the path template.
+    #[arg(
+      help = "\
+The API request path or the path template,
+for example, `chat/completions` or `threads/{thread_id}/messages`.",
+      value_name = "PATH",
+    )]
+    path: String,
+  },
+
+  /// Work with the OpenAPI specifications.
+  Spec {
+    /// The subcommand of the OpenAPI specifications.
+    #[command(subcommand)]
+    command: SpecCommand,
+  },
+}
+
+/// The subcommands of the configuration of the API request.
+#[derive(Subcommand)]
+pub enum ConfigCommand {
+  /// Explain which layer each field of the API request parameters comes from.
+  #[command(
+    long_about = "\
+Explain which layer each field of the API request parameters (body) comes from.
+The layers are merged in order: the defaults of the endpoint in the file of the setting `OPENAI_DEFAULTS`,
+the parameter files of <PARAM_FILE_PATH> or the standard input with their base files of `extends`,
+and the inline items.",
+  )]
+  Explain {
+    /// The API request path.
+    #[arg(
+      help = "\
+The API request path, for example, `chat/completions`.",
+      value_name = "PATH",
+    )]
+    path: String,
+
+    /// The inline items of the API request parameters (body).
+    #[arg(
+      help = "\
+The inline items of the API request parameters (body), for example, `model=gpt-4` or `temperature:=0.2`.",
+      value_name = "ITEM",
+    )]
+    items: Vec<ParameterItem>,
+  },
+}
+
+/// The subcommands of the OpenAPI specifications.
+#[derive(Subcommand)]
+pub enum SpecCommand {
+  /// Report the endpoint changes between two OpenAPI specifications.
+  #[command(
+    long_about = "\
+Report the endpoint changes between two OpenAPI specifications in YAML or JSON format,
+including the added and removed paths, the changed methods, the new required request fields,
+the removed response fields and the newly deprecated operations.",
+  )]
+  Diff {
+    /// The format of the report.
+    #[arg(
+      default_value = "text",
+      help = "\
+The format of the report. The format `json` writes one JSON object.",
+      long,
+      value_enum,
+      value_name = "FORMAT",
+    )]
+    format: DiffFormat,
+
+    /// The file path of the old OpenAPI specification.
+    #[arg(
+      help = "\
+The file path of the old OpenAPI specification.",
+      value_name = "OLD_SPEC_FILE_PATH",
+    )]
+    old: PathBuf,
+
+    /// The file path of the new OpenAPI specification.
+    #[arg(
+      help = "\
+The file path of the new OpenAPI specification.",
+      value_name = "NEW_SPEC_FILE_PATH",
+    )]
+    new: PathBuf,
+  },
+}
+
+/// The format of the report of the specification diff.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, ValueEnum)]
+pub enum DiffFormat {
+  /// The readable text.
+  #[default]
+  Text,
+
+  /// The JSON object.
+  Json,
+}
+
+impl Command {
+  /// Run the subcommand in the context and write the result to the output writer.
+  ///
+  /// The catalog of the context is used, which is the embedded one if the specification is not fetched.
+  pub fn run(self, context: &Context, output: &mut dyn Write) -> Result<()> {
+    match self {
+      Command::Config { command: ConfigCommand::Explain { path, items } } => {
+        config_explain(context, path, items, output)
+      },
+      Command::Describe { path } => describe(context, &path, output),
+      Command::Endpoints { filter } => endpoints(context, filter.as_deref(), output),
+      Command::Init { force, path } => init(context, &path, force, output),
+      Command::Spec { command: SpecCommand::Diff { format, old, new } } => {
+        spec_diff(context, &old, &new, format, output)
+      },
+    }
+  }
+}
+
+/// Returns the endpoint of the API request path or the path template in the catalog.
+fn find_endpoint<'a>(catalog: &'a Catalog, path: &str) -> Result<&'a Endpoint> {
+  if let Some(endpoint) = catalog.endpoint(Path::normalize(path)) {
+    return Ok(endpoint);
+  }
+  let path = Path::with_catalog(catalog, path, &[])?;
+  catalog
+    .endpoint(path.template())
+    .ok_or(Error::msg(format!("The endpoint {:?} is not found", path.template())))
+}
+
+/// Returns the status of the deprecated or legacy operation.
+fn status(operation: &Operation) -> Option<&'static str> {
+  if operation.deprecated {
+    Some("deprecated")
+  } else if operation.legacy {
+    Some("legacy")
+  } else {
+    None
+  }
+}
+
+fn config_explain(context: &Context, path: String, items: Vec<ParameterItem>, output: &mut dyn Write) -> Result<()> {
+  let mut context = context.clone();
+  context.args.path = path;
+  context.args.items.extend(items);
+  let path = Path::resolve(&context)?;
+  let layers = Parameter::fetch_layers(&context)?;
+  if layers.is_empty() {
+    return Err(Error::msg(format!("No API request parameters are found for {:?}", path.template())));
+  }
+
+  let fields: Vec<_> = layers
+    .fields()
+    .into_iter()
+    .map(|(pointer, value, source)| {
+      let pointer = if pointer.is_empty() { "/".to_string() } else { pointer };
+      let mut value = value.to_string();
+      if value.chars().count() > 40 {
+        value = format!("{}...", value.chars().take(37).collect::<String>());
+      }
+      (pointer, value, source)
+    })
+    .collect();
+  let pointer_width = fields.iter().map(|(pointer, ..)| pointer.len()).max().unwrap_or_default();
+  let value_width = fields.iter().map(|(_, value, _)| value.chars().count()).max().unwrap_or_default();
+  for (pointer, value, source) in fields {
+    writeln!(output, "{pointer:<pointer_width$}  {value:<value_width$}  {source}")?;
+  }
+  Ok(())
+}
+
+fn describe(context: &Context, path: &str, output: &mut dyn Write) -> Result<()> {
+  let endpoint = find_endpoint(context.catalog(), path)?;
+  let params = Template::from_str(&endpoint.path)?
+    .params()
+    .iter()
+    .map(|name| format!("`{name}`"))
+    .collect::<Vec<_>>();
+
+  for (index, operation) in endpoint.operations.iter().enumerate() {
+    if index > 0 {
+      writeln!(output)?;
+    }
+    writeln!(output, "{} {}", operation.method, endpoint.path)?;
+    if !operation.summary.is_empty() {
+      writeln!(output, "  {}", operation.summary)?;
+    }
+    writeln!(output, "  Operation ID: {}", operation.operation_id)?;
+    if let Some(status) = status(operation) {
+      writeln!(output, "  Status: {status}")?;
+    }
+    if !params.is_empty() {
+      writeln!(output, "  Path parameters: {}", params.join(", "))?;
+    }
+    let Some(request) = operation.request.as_ref() else {
+      writeln!(output, "  Request body: none")?;
+      continue;
+    };
+    writeln!(output, "  Request body ({}):", request.content_type)?;
+    let fields = Schema(&request.schema).fields();
+    for (heading, required) in [("Required fields", true), ("Optional fields", false)] {
+      let fields: Vec<_> = fields.iter().filter(|field| field.required == required).collect();
+      if fields.is_empty() {
+        continue;
+      }
+      writeln!(output, "    {heading}:")?;
+      for field in fields {
+        write!(output, "      {}: {}", field.name, field.schema.type_name())?;
+        if let Some(default) = field.schema.default_value() {
+          write!(output, " (default: {default})")?;
+        }
+        writeln!(output)?;
+        if let Some(description) = field.schema.description() {
+          writeln!(output, "        {description}")?;
+        }
+      }
+    }
+    if let Some(example) = request.example.as_ref() {
+      writeln!(output, "  Example body:")?;
+      for line in serde_json::to_string_pretty(example)?.lines() {
+        writeln!(output, "    {line}")?;
+      }
+    }
+  }
+  Ok(())
+}
+
+fn endpoints(context: &Context, filter: Option<&str>, output: &mut dyn Write) -> Result<()> {
+  let filter = filter.map(str::to_lowercase);
+  let operations: Vec<_> = context
+    .catalog()
+    .endpoints
+    .iter()
+    .flat_map(|endpoint| endpoint.operations.iter().map(move |operation| (endpoint, operation)))
+    .filter(|(endpoint, operation)| {
+      filter.as_ref().map_or(true, |filter| {
+        [&operation.method, &endpoint.path, &operation.operation_id, &operation.summary]
+          .iter()
+          .any(|text| text.to_lowercase().contains(filter))
+      })
+    })
+    .collect();
+  if operations.is_empty() {
+    return Err(Error::msg(format!(
+      "No API operations match the filter {:?}",
+      filter.unwrap_or_default(),
+    )));
+  }
+
+  let width = operations
+    .iter()
+    .map(|(endpoint, _)| endpoint.path.len())
+    .max()
+    .unwrap_or_default();
+  for (endpoint, operation) in operations {
+    let summary = match status(operation) {
+      Some(status) => format!("({status}) {}", operation.summary),
+      None => operation.summary.clone(),
+    };
+    writeln!(output, "{:<6} {:<width$}  {}", operation.method, endpoint.path, summary)?;
+  }
+  Ok(())
+}
+
+fn init(context: &Context, path: &str, force: bool, output: &mut dyn Write) -> Result<()> {
+  let endpoint = find_endpoint(context.catalog(), path)?;
+  let (operation, request) = endpoint.operations
+    .iter()
+    .filter_map(|operation| Some((operation, operation.request.as_ref()?)))
+    .min_by_key(|(operation, _)| operation.method != "POST")
+    .ok_or(Error::msg(format!(
+      "The endpoint {:?} does not accept the API request parameters (body)",
+      endpoint.path,
+    )))?;
+  if request.content_type != mime::APPLICATION_JSON.as_ref() {
+    return Err(Error::msg(format!(
+      "The API request parameters (body) of {} {} are in the content type {:?}, \
+      which is not supported by the parameter file",
+      operation.method, endpoint.path, request.content_type,
+    )));
+  }
+
+  let schema = Schema(&request.schema);
+  let example = request.example.as_ref().and_then(Value::as_object);
+  let mut parameter = Map::new();
+  let mut optional = vec![];
+  for field in schema.fields() {
+    match example.and_then(|example| example.get(field.name)) {
+      Some(value) => {
+        parameter.insert(field.name.to_string(), value.clone());
+      },
+      None if field.required => {
+        parameter.insert(field.name.to_string(), field.schema.placeholder());
+      },
+      None => optional.push(format!("`{}`", field.name)),
+    }
+  }
+  for (name, value) in example.into_iter().flatten() {
+    if !parameter.contains_key(name) {
+      parameter.insert(name.clone(), value.clone());
+    }
+  }
+
+  let file = context.args.parameter_files
+    .first()
+    .cloned()
+    .unwrap_or_else(|| PathBuf::from(Parameter::DEFAULT_PATHS[0]));
+  if !force && context.read(&file).is_ok() {
+    return Err(Error::msg(format!(
+      "The parameter file {file:?} already exists, provide the option `--force` to overwrite it",
+    )));
+  }
+  let mut writer = context.create(&file)?;
+  serde_json::to_writer_pretty(&mut writer, &Value::Object(parameter))?;
+  writeln!(writer)?;
+  writer.flush()?;
+  info!("Successfully created the parameter file {file:?} for {} {}", operation.method, endpoint.path);
+
+  writeln!(output, "Created the parameter file {file:?} for {} {}", operation.method, endpoint.path)?;
+  if !optional.is_empty() {
+    writeln!(output, "The optional fields are: {}", optional.join(", "))?;
+  }
+  writeln!(output, "Run `openai-client describe {}` for the details of the fields", endpoint.path)?;
+  Ok(())
+}
+
+fn spec_diff(
+  context: &Context,
+  old: &path::Path,
+  new: &path::Path,
+  format: DiffFormat,
+  output: &mut dyn Write,
+) -> Result<()> {
+  let load = |path: &path::Path| {
+    context
+      .read(path)
+      .and_then(|bytes| Spec::from_slice(&bytes))
+      .map_err(|err| err.context(format!("Failed to load the OpenAPI specification {path:?}")))
+  };
+  let diff = CatalogDiff::new(load(old)?.value_ref(), load(new)?.value_ref());
+  match format {
+    DiffFormat::Text => write!(output, "{diff}")?,
+    DiffFormat::Json => writeln!(output, "{}", serde_json::to_string_pretty(&diff)?)?,
+  }
+  Ok(())
+}
@@ -0,0 +1,371 @@
+use crate::{Catalog, Error, ParameterItem, Result, Router, loaders::{self, *}, service::*, traits::*};
+use std::{
+  collections::HashMap,
+  env,
+  fs::{self, File},
+  io::{self, IsTerminal, Read, Write},
+  path::{Path, PathBuf},
+  sync::{Arc, Mutex},
+};
+use regex::Regex;
+use tracing::{debug, info};
+
+/// The arguments for loaders.
+#[derive(Clone, Default)]
+pub struct Arguments {
+  /// Whether the API response is appended to the output file instead of replacing it.
+  pub append: bool,
+
+  /// The mode of checking the API response against the response schema, which is disabled if not provided.
+  pub check_response: Option<ResponseCheck>,
+
+  /// Whether the piped or redirected standard input is ignored.
+  pub ignore_stdin: bool,
+
+  /// The inline items merged into the API request parameters (body) in order.
+  pub items: Vec<ParameterItem>,
+
+  /// The file path where the API key is stored.
+  pub key_file: Option<PathBuf>,
+
+  /// The HTTP method used for the API request.
+  pub method: Option<String>,
+
+  /// Whether the missing parent directories of the output file are created.
+  pub mkdir: bool,
+
+  /// Whether the validation of the API request parameters is skipped.
+  pub no_validate: bool,
+
+  /// The file path where the organization ID is stored.
+  pub organization_file: Option<PathBuf>,
+
+  /// The directory where the API responses are stored in the files named by `output_name`.
+  pub output_dir: Option<PathBuf>,
+
+  /// The file path where the API response will be stored.
+  pub output_file: Option<PathBuf>,
+
+  /// The template of the file names in the output directory, or the default one if not provided.
+  pub output_name: Option<String>,
+
+  /// The API request parameters (body), which are fetched before the HTTP method.
+  pub parameter: Option<Parameter>,
+
+  /// The file paths where the API request parameters (body) are stored in JSON, JSON5, TOML or YAML format,
+  /// which are merged in order.
+  pub parameter_files: Vec<PathBuf>,
+
+  /// The API request path.
+  pub path: String,
+
+  /// The path parameters to be substituted into the API request path.
+  pub path_params: Vec<(String, String)>,
+
+  /// Whether the metadata of the API request and response is saved next to the output file.
+  pub save_meta: bool,
+
+  /// The catalog of the OpenAPI specification, which is fetched before the API request path.
+  pub spec: Option<Spec>,
+
+  /// The file path where the OpenAPI specification is stored in YAML or JSON format.
+  pub spec_file: Option<PathBuf>,
+
+  /// Whether the API response is also written to the standard output besides the output file.
+  pub tee: bool,
+
+  /// The variables of the parameter templates.
+  pub vars: Vec<(String, String)>,
+
+  /// The file path where the variables of the parameter templates are stored.
+  pub vars_file: Option<PathBuf>,
+}
+
+/// The resolution context for loaders.
+///
+/// Loaders read the arguments, the environment variables and the files
+/// from the context instead of the process.
+#[derive(Clone)]
+pub struct Context {
+  /// The arguments.
+  pub args: Arguments,
+
+  /// The current working directory, where the relative paths are resolved from.
+  pub current_dir: PathBuf,
+
+  /// The environment variables.
+  pub env: HashMap<String, String>,
+
+  /// The file system.
+  pub fs: Arc<dyn FileSystem>,
+
+  /// The home directory, where the paths starting with `~` are resolved from.
+  pub home_dir: Option<PathBuf>,
+}
+
+impl Context {
+  /// The default profile files where the settings such as `OPENAI_SPEC` are searched in order.
+  pub const PROFILE_PATHS: [&'static str; 6] = [
+    "openai.env",
+    ".openai_profile",
+    ".env",
+    "~/openai.env",
+    "~/.openai_profile",
+    "~/.env",
+  ];
+
+  /// Create a new context from the environment of the current process.
+  pub fn new(args: Arguments) -> Result<Self> {
+    Ok(Self {
+      args,
+      current_dir: env::current_dir()?,
+      env: env::vars().collect(),
+      fs: Arc::new(OsFileSystem),
+      home_dir: dirs::home_dir(),
+    })
+  }
+
+  /// Resolve the path against the current working directory and the home directory.
+  pub fn resolve_path<P>(&self, path: P) -> PathBuf
+  where
+    P: AsRef<Path>,
+  {
+    let path = path.as_ref();
+    let path = match (path.strip_prefix("~"), self.home_dir.as_ref()) {
+      (Ok(relative), Some(home_dir)) => home_dir.join(relative),
+      _ => path.to_path_buf(),
+    };
+    self.current_dir.join(path)
+  }
+
+  /// Read the entire contents of a file into bytes.
+  pub fn read<P>(&self, path: P) -> Result<Vec<u8>>
+  where
+    P: AsRef<Path>,
+  {
+    Ok(self.fs.read(&self.resolve_path(path))?)
+  }
+
+  /// Read the entire contents of a file into a string.
+  pub fn read_to_string<P>(&self, path: P) -> Result<String>
+  where
+    P: AsRef<Path>,
+  {
+    Ok(String::from_utf8(self.read(path)?)?)
+  }
+
+  /// Returns the reader of the standard input if it is piped or redirected and not ignored,
+  /// or if `force` is set.
+  pub fn stdin(&self, force: bool) -> Option<Box<dyn Read>> {
+    (force || !self.args.ignore_stdin && !self.fs.stdin_is_terminal()).then(|| self.fs.stdin())
+  }
+
+  /// Create a file, or truncate it if it exists, and returns the writer of it.
+  pub fn create<P>(&self, path: P) -> Result<Box<dyn Write>>
+  where
+    P: AsRef<Path>,
+  {
+    Ok(self.fs.create(&self.resolve_path(path))?)
+  }
+
+  /// Returns the catalog of the fetched OpenAPI specification, or the embedded one if not fetched.
+  pub fn catalog(&self) -> &Catalog {
+    self.args.spec
+      .as_ref()
+      .map_or(Catalog::embedded(), |spec| spec.value_ref())
+  }
+
+  /// Returns the router over the catalog, which is built once with the spec.
+  pub fn router(&self) -> Router<'_> {
+    self.args.spec
+      .as_ref()
+      .map_or_else(|| Router::embedded().borrowed(), |spec| spec.router())
+  }
+
+  /// Returns the value of the environment variable.
+  pub fn var(&self, key: &str) -> Result<&String> {
+    self.env
+      .get(key)
+      .ok_or(Error::msg("environment variable not found"))
+  }
+
+  /// Returns the value of the setting and its source,
+  /// which is obtained from the environment variable or else from the default profile files.
+  pub fn setting(&self, name: &str) -> Result<(String, String)> {
+    let source = format!("the environment variable `{name}`");
+    match self.var(name) {
+      Ok(value) => return Ok((value.clone(), source)),
+      Err(err) => debug!("Failed to obtain the setting `{name}` from {source}: {err:?}"),
+    }
+
+    let pattern = Regex::new(&format!(r#"(?m)^\s*(?:export\s+)?{name}\s*=\s*["']?([^"'\s]+)"#))?;
+    let not_found = || Error::msg(format!("The setting `{name}` is not found"));
+    for default_file in Self::PROFILE_PATHS {
+      let source = format!("the setting `{name}` in the default file {default_file:?}");
+      match self.read_to_string(default_file).and_then(|text| {
+        Ok(pattern.captures(&text).and_then(|captures| captures.get(1)).ok_or_else(not_found)?.as_str().to_string())
+      }) {
+        Ok(value) => return Ok((value, source)),
+        Err(err) => debug!("Failed to obtain the setting `{name}` from {source}: {err:?}"),
+      }
+    }
+    Err(not_found())
+  }
+
+  /// Resolve the API request parameters, path and method.
+  ///
+  /// The parameters are validated against the request schema of the operation
+  /// unless `no_validate` is set, and a warning is logged if the field `model` is deprecated.
+  pub fn resolve(&mut self) -> Result<(loaders::Path, Method)> {
+    if self.args.spec.is_none() {
+      self.args.spec = Some(Spec::fetch(self)?);
+    }
+    // `parameter` should be fetched before `method`
+    let parameter = Parameter::fetch_layers(self)?.value().map(Parameter::from);
+    if parameter.is_none() {
+      info!("Ignored the field `parameter` for not being fetched successfully");
+    }
+    self.args.parameter = parameter;
+    let path = loaders::Path::fetch(self)?;
+    let method = Method::fetch(self)?;
+    if let Some(parameter) = self.args.parameter.as_ref() {
+      parameter.warn_deprecated_model();
+      if !self.args.no_validate {
+        parameter.validate(self.catalog(), &path, &method)?;
+      }
+    }
+    Ok((path, method))
+  }
+
+  /// Run the program in the context.
+  ///
+  /// It sends the API request with the resolved arguments and resolves the API response.
+  pub async fn run(mut self) -> Result<OpenAIResponse> {
+    let key = Key::fetch(&self)?;
+    let organization = Organization::fetch(&self).ok();
+    if organization.is_none() {
+      info!("Ignored the field `organization` for not being fetched successfully");
+    }
+    let (path, method) = self.resolve()?;
+
+    let endpoint = self.catalog().endpoint(path.template()).cloned();
+    let method_name = method.value_ref().to_string();
+    let client = OpenAIClient::new(key, organization);
+    let request = OpenAIRequest::new(method, path, self.args.parameter)?;
+    let response = client.send(request).await?;
+    match (endpoint, self.args.check_response) {
+      (Some(endpoint), Some(mode)) => response.check(&endpoint, &method_name, mode),
+      _ => Ok(response),
+    }
+  }
+}
+
+/// The file system of the operating system.
+pub struct OsFileSystem;
+
+impl FileSystem for OsFileSystem {
+  fn read(&self, path: &Path) -> io::Result<Vec<u8>> {
+    fs::read(path)
+  }
+  fn exists(&self, path: &Path) -> bool {
+    path.exists()
+  }
+  fn append(&self, path: &Path) -> io::Result<Box<dyn Write>> {
+    Ok(Box::new(File::options().append(true).create(true).open(path)?))
+  }
+  fn create(&self, path: &Path) -> io::Result<Box<dyn Write>> {
+    Ok(Box::new(File::create(path)?))
+  }
+  fn create_dir_all(&self, path: &Path) -> io::Result<()> {
+    fs::create_dir_all(path)
+  }
+  fn remove_file(&self, path: &Path) -> io::Result<()> {
+    fs::remove_file(path)
+  }
+  fn rename(&self, from: &Path, to: &Path) -> io::Result<()> {
+    fs::rename(from, to)
+  }
+  fn stdin(&self) -> Box<dyn Read> {
+    Box::new(io::stdin().lock())
+  }
+  fn stdin_is_terminal(&self) -> bool {
+    io::stdin().is_terminal()
+  }
+}
+
+/// The file system in memory, in which the directories always exist.
+///
+/// The standard input is the contents of the file `-`, and it is a terminal if the file does not exist.
+#[derive(Clone, Default)]
+pub struct MemoryFileSystem(Arc<Mutex<HashMap<PathBuf, Vec<u8>>>>);
+
+impl MemoryFileSystem {
+  /// Returns the contents of a file.
+  pub fn get<P>(&self, path: P) -> Option<Vec<u8>>
+  where
+    P: AsRef<Path>,
+  {
+    self.0.lock().unwrap().get(path.as_ref()).cloned()
+  }
+
+  /// Insert a file with the contents.
+  pub fn insert<P, C>(&self, path: P, contents: C)
+  where
+    P: Into<PathBuf>,
+    C: Into<Vec<u8>>,
+  {
+    self.0.lock().unwrap().insert(path.into(), contents.into());
+  }
+}
+
+impl FileSystem for MemoryFileSystem {
+  fn read(&self, path: &Path) -> io::Result<Vec<u8>> {
+    self.get(path).ok_or(io::ErrorKind::NotFound.into())
+  }
+  fn exists(&self, path: &Path) -> bool {
+    self.0.lock().unwrap().contains_key(path)
+  }
+  fn append(&self, path: &Path) -> io::Result<Box<dyn Write>> {
+    self.0.lock().unwrap().entry(path.to_path_buf()).or_default();
+    Ok(Box::new(MemoryFile(self.clone(), path.to_path_buf())))
+  }
+  fn create(&self, path: &Path) -> io::Result<Box<dyn Write>> {
+    self.insert(path, vec![]);
+    Ok(Box::new(MemoryFile(self.clone(), path.to_path_buf())))
+  }
+  fn create_dir_all(&self, _path: &Path) -> io::Result<()> {
+    Ok(())
+  }
+  fn remove_file(&self, path: &Path) -> io::Result<()> {
+    self.0.lock().unwrap().remove(path).map(|_| ()).ok_or(io::ErrorKind::NotFound.into())
+  }
+  fn rename(&self, from: &Path, to: &Path) -> io::Result<()> {
+    let mut files = self.0.lock().unwrap();
+    let contents = files.remove(from).ok_or(io::Error::from(io::ErrorKind::NotFound))?;
+    files.insert(to.to_path_buf(), contents);
+    Ok(())
+  }
+  fn stdin(&self) -> Box<dyn Read> {
+    Box::new(io::Cursor::new(self.get("-").unwrap_or_default()))
+  }
+  fn stdin_is_terminal(&self) -> bool {
+    self.get("-").is_none()
+  }
+}
+
+struct MemoryFile(MemoryFileSystem, PathBuf);
+
+impl Write for MemoryFile {
+  fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+    self.0.0
+      .lock()
+      .unwrap()
+      .entry(self.1.clone())
+      .or_default()
+      .extend_from_slice(buf);
+    Ok(buf.len())
+  }
+  fn flush(&mut self) -> io::Result<()> {
+    Ok(())
+  }
+}
@@ -1,12 +1,7 @@
 use crate::*;
-use clap::{arg, command};
-use eventsource_stream::Eventsource;
-use futures_util::StreamExt;
-use http::header::CONTENT_TYPE;
-use mime::Mime;
-use std::path::PathBuf;
-use std::io::stderr;
-use tracing::{info, Level};
+use clap::{arg, command, ArgAction};
+use regex::Regex;
+use std::{io::{stdout, Write}, path::PathBuf};
 
 #[doc(hidden)]
 pub use clap::Parser;
@@ -28,8 +23,95 @@ pub use clap::Parser;
 {after-help}",
   version,
   next_line_help = true,
+  override_usage = "\
+openai-client [OPTIONS] <PATH> [ITEM]...
+       openai-client [OPTIONS] <COMMAND>",
+  subcommand_negates_reqs = true,
 )]
 pub struct Entry {
+  /// Switch for appending the API response to the output file.
+  #[arg(
+    default_value = "false",
+    help = "\
+Switch for appending the API response to the output file instead of replacing it,
+which is created if it does not exist. It is disabled by default.
+",
+    long,
+    requires = "output_file",
+  )]
+  pub append: bool,
+
+  /// The mode of checking the API response against the response schema.
+  #[arg(
+    default_missing_value = "warn",
+    help = "\
+Check the API response against the response schema of the operation in the OpenAPI specification,
+which detects the missing required fields, the unexpected types and the undocumented fields.
+The JSON body is checked as a whole and the server-sent events are checked chunk by chunk.
+The violations are logged as warnings in the mode `warn` (by default),
+or reported as errors in the mode `strict`.
+It is disabled if not provided.
+",
+    long,
+    num_args = 0..=1,
+    value_enum,
+    value_name = "MODE",
+  )]
+  pub check_response: Option<ResponseCheck>,
+
+  /// The subcommand, which is run instead of sending the API request.
+  #[command(subcommand)]
+  pub command: Option<Command>,
+
+  /// The format of the exported API response.
+  #[arg(
+    default_value = "pretty",
+    help = "\
+The format of the exported API response (body):
+ - `pretty` writes the indented JSON.
+ - `compact` writes the JSON in one line.
+ - `ndjson` writes the items of the list response in the field `data` one per line.
+ - `yaml` writes the YAML documents.
+ - `raw` writes the JSON body as received, byte for byte.
+The data in the server-sent events are written one by one in the format,
+in which `pretty` writes each of them in one line to keep the stream line by line,
+and `raw` writes each of them as received without the framing of the events, such as `data:`.
+",
+    long,
+    value_enum,
+    value_name = "FORMAT",
+  )]
+  pub format: OutputFormat,
+
+  /// Switch for ignoring the piped or redirected standard input.
+  #[arg(
+    default_value = "false",
+    help = "\
+Switch for ignoring the piped or redirected standard input,
+which is otherwise read as the API request parameters (body) if <PARAM_FILE_PATH> is not provided.
+It is useful in scripts where the standard input is never closed. It is disabled by default.
+",
+    long,
+  )]
+  pub ignore_stdin: bool,
+
+  /// The inline items of the API request parameters (body).
+  #[arg(
+    help = "\
+The inline items of the API request parameters (body), which are merged on top of <PARAM_FILE_PATH> in order.
+The formats of <ITEM> are:
+ - `NAME=VALUE` sets the field to the string, for example, `model=gpt-4`.
+ - `NAME:=JSON` sets the field to the raw JSON value, for example, `temperature:=0.2`.
+ - `NAME=@FILE` sets the field to the contents of the file as a string.
+ - `NAME:=@FILE` sets the field to the contents of the file as a raw JSON value.
+The <NAME> can address nested fields and array elements,
+for example, `messages[0].content=@prompt.txt`, or append to an array with `[]`, for example, `stop[]=END`.
+",
+    index = 2,
+    value_name = "ITEM",
+  )]
+  pub items: Vec<ParameterItem>,
+
   /// The file path where the API key is stored.
   #[arg(
     help = "\
@@ -48,15 +130,57 @@ The program will attempt the following steps to obtain a valid API key:
   )]
   pub key_file: Option<PathBuf>,
 
+  /// The file path where the logs are appended to.
+  #[arg(
+    help = "\
+The file path where the logs are appended to.
+The logs are written to the standard error if it is not provided.
+The final error is always reported to the standard error, and also logged to the file.
+",
+    long,
+    value_name = "LOG_FILE_PATH",
+  )]
+  pub log_file: Option<PathBuf>,
+
+  /// The filter directives of logging.
+  #[arg(
+    env = "OPENAI_LOG",
+    help = "\
+The filter directives of logging in the same syntax as `RUST_LOG`,
+for example, `info` or `warn,openai_client_cli=debug`.
+The verbosity options `-v`, `-q` and `--trace` are ignored if it is provided.
+",
+    long,
+    value_name = "FILTER",
+  )]
+  pub log_filter: Option<String>,
+
+  /// The format of logs.
+  #[arg(
+    default_value = "text",
+    help = "\
+The format of logs. The format `json` writes one JSON object per line.
+",
+    long,
+    value_enum,
+    value_name = "LOG_FORMAT",
+  )]
+  pub log_format: LogFormat,
+
   /// The HTTP method used for the API request.
   #[arg(
     help = "\
 The HTTP method used for the API request.
 The program will attempt the following steps to determine a valid HTTP method:
- 1. Read the value of argument <METHOD>.
- 2. If the `parameter` object is successfully fetched from either
+ 1. Read the value of argument <METHOD>. Exit the program with a non-zero return code
+    if the OpenAPI specification does not allow it on <PATH>.
+ 2. If the OpenAPI specification allows only one method on <PATH>, set <METHOD> to it.
+ 3. If the `parameter` object is successfully fetched from either
     <PARAM_FILE_PATH> or one of the default paths, set <METHOD> to `POST`.
- 3. Otherwise, set <METHOD> to `GET`.
+ 4. Otherwise, set <METHOD> to `GET`.
+    If the OpenAPI specification does not allow the method of step 3 or 4 on <PATH>,
+    set <METHOD> to the first allowed method instead.
+A warning is logged if the `parameter` object will be sent with `GET`.
 ",
     long,
     short = 'm',
@@ -64,6 +188,30 @@ The program will attempt the following steps to determine a valid HTTP method:
   )]
   pub method: Option<String>,
 
+  /// Switch for creating the missing parent directories of the output file.
+  #[arg(
+    default_value = "false",
+    help = "\
+Switch for creating the missing parent directories of the output file. It is disabled by default.
+",
+    long,
+    requires = "output_file",
+  )]
+  pub mkdir: bool,
+
+  /// Switch for skipping the validation of the API request parameters.
+  #[arg(
+    default_value = "false",
+    help = "\
+Switch for skipping the validation of the API request parameters (body).
+The parameters are validated against the request schema of the operation
+in the OpenAPI specification before sending the API request by default.
+The errors name the JSON pointers, the expected types or enums, and the unknown fields.
+",
+    long,
+  )]
+  pub no_validate: bool,
+
   /// The file path where the organization ID is stored.
   #[arg(
     help = "\
@@ -83,12 +231,29 @@ The program will attempt the following steps to obtain a valid organization ID:
   )]
   pub organization_file: Option<PathBuf>,
 
+  /// The directory where the API responses are stored in the files named by the template.
+  #[arg(
+    conflicts_with_all = ["append", "output_file"],
+    help = "\
+The directory where the API responses are stored, which is created if it does not exist.
+Each API response is stored in a new file named by the template of the option `--output-name`,
+and a line of the file name, the timestamp, the HTTP method, the API request path, the status code,
+the request ID and the API request parameters (body) is appended to the file `index.jsonl` in the directory.
+It is useful for the requests repeated many times, such as sampling, sweeps or scheduled jobs.
+",
+    long,
+    value_name = "OUTPUT_DIR",
+  )]
+  pub output_dir: Option<PathBuf>,
+
   /// The file path where the API response will be stored.
   #[arg(
     help = "\
 The file path where the API response will be stored.
 The program will attempt the following steps to successfully store the response:
  1. Export the output to the provided file path <OUTPUT_FILE_PATH>.
+    The output is written to a temporary file next to it, which replaces the file
+    only if the API response is successfully exported, unless the option `--append` is provided.
  2. Export the output to the standard output.
  3. Exit the program with a non-zero return code.
 ",
@@ -98,169 +263,389 @@ The program will attempt the following steps to successfully store the response:
   )]
   pub output_file: Option<PathBuf>,
 
-  /// The file path where the API request parameters (body) are stored in JSON format.
+  /// The template of the file names in the output directory.
   #[arg(
     help = "\
-The file path where the API request parameters (body) are stored in JSON format.
+The template of the file names in the output directory,
+which is `{timestamp}-{path}-{request_id}.{ext}` by default. The placeholders are:
+ - `{timestamp}`: the UTC time when the file is stored, for example, `20240101T120000.123Z`.
+ - `{path}`: the API request path, whose `/` are replaced with `-`, for example, `chat-completions`.
+ - `{request_id}`: the request ID in the header `x-request-id` of the API response.
+ - `{method}`: the HTTP method in lowercase.
+ - `{ext}`: the file extension following the exported format, which is `yaml` for `--format yaml`,
+   `jsonl` for `--format ndjson` and `txt` for `--select` with `-r`, or else the content type of the API response,
+   which is `json` for JSON and `jsonl` for server-sent events.
+If the file already exists, a counter is appended to the name, for example, `...-1.json`.
+",
+    long,
+    requires = "output_dir",
+    value_name = "TEMPLATE",
+    value_parser = parse_output_name,
+  )]
+  pub output_name: Option<String>,
+
+  /// The file path where the API request parameters (body) are stored in JSON, JSON5, TOML or YAML format.
+  #[arg(
+    help = "\
+The file path where the API request parameters (body) are stored in JSON, JSON5, TOML or YAML format.
+The format is chosen by the file extension: `.json`, `.json5`, `.jsonc`, `.toml`, `.yaml` or `.yml`,
+otherwise it is sniffed from the contents.
 The program will attempt the following steps to obtain a valid parameter object:
- 1. Read the file from the provided path <PARAM_FILE_PATH>.
- 2. Read the file from the default paths in the following order:
+ 1. Read the files from the provided paths <PARAM_FILE_PATH>s,
+    or read the standard input if <PARAM_FILE_PATH> is `-`.
+    This option can be provided multiple times, and the files are deep-merged in order.
+ 2. Read the standard input if <PARAM_FILE_PATH> is not provided
+    and the standard input is piped or redirected, for example, `jq ... | openai-client ...`,
+    unless the option `--ignore-stdin` is provided.
+    A JSON object is streamed, and the other formats are buffered to be sniffed from the contents.
+    It fails if the standard input is not empty but invalid.
+ 3. Read the file from the default paths in the following order:
     `openai.json`, `openai-parameters.json`, `openai_parameters.json`,
     `openai-parameters`, `openai_parameters`, or `openai.config.json`.
- 3. Ignore the field and leave it empty
+ 4. Ignore the field and leave it empty
+The files are merged with the semantics of JSON Merge Patch, in which the objects are merged,
+the fields of `null` remove the fields below them, and the other values including arrays are replaced.
+The fields of `null` in the first layer are kept as they are.
+The value `{\"$append\": [...]}` appends to the array and `{\"$replace\": ...}` replaces the value explicitly.
+The field `extends` of a file refers to its base files, which are merged under it.
+The defaults of each endpoint in the file of the setting `OPENAI_DEFAULTS`, for example,
+`{\"chat/completions\": {\"model\": \"gpt-4\"}}`, are merged first.
+Run `openai-client config explain <PATH>` to show which layer each field comes from.
+In the string values of the files, the placeholders `{{NAME}}` are filled by <VAR>s
+and `${ENV}` is expanded to the environment variable, where `$${{` and `$${` are the escaped `{{` and `${`.
+Then the string values of `@FILE` and the objects of `{\"$include\": \"FILE\"}` are replaced with
+the contents of the files, which are inlined as they are without rendering the placeholders.
+The standard input and the inline items are not rendered either.
+NOTE: Every string value starting with `@` in the files is read as a file path,
+so a literal `@` at the start must be escaped as `@@`, for example, `\"@@channel please\"`.
 ",
     long,
     short = 'p',
     value_name = "PARAM_FILE_PATH",
   )]
-  pub parameter_file: Option<PathBuf>,
-
-  /// Hidden.
-  #[arg(hide = true, long, exclusive = true)]
-  pub _parameter: Option<Parameter>,
+  pub parameter_file: Vec<PathBuf>,
 
   /// The API request path. (part of the URL)
   #[arg(
     help = "\
 The API request path. (part of the URL)
-The program will use regex to extract the matched segment in <PATH>.
-For example, the extracted strings will be the same when <PATH> is either
-`chat/completions`, `/chat/completions` or `https://api.openai.com/v1/chat/completions`.",
+The program will match the whole <PATH> against the endpoints in the OpenAPI specification
+after removing the origin and the version prefix `v1`.
+For example, the matched paths will be the same when <PATH> is either
+`chat/completions`, `/chat/completions` or `https://api.openai.com/v1/chat/completions`.
+The path parameters in curly braces are substituted with <PATH_PARAM>s,
+for example, `files/{file_id}/content --path-param file_id=file-abc`.",
+    index = 1,
+    required = true,
     value_name = "PATH",
   )]
-  pub path: String,
+  pub path: Option<String>,
 
-  /// Switch for verbose logging mode.
+  /// The path parameters.
+  #[arg(
+    help = "\
+The path parameter to be substituted into <PATH> in the format of `NAME=VALUE`.
+This option can be provided multiple times.
+",
+    long,
+    value_name = "PATH_PARAM",
+    value_parser = parse_path_param,
+  )]
+  pub path_param: Vec<(String, String)>,
+
+  /// The quietness of logging.
+  #[arg(
+    action = ArgAction::Count,
+    help = "\
+The quietness of logging. `-q` only logs the errors and `-qq` logs nothing.
+",
+    long,
+    short = 'q',
+  )]
+  pub quiet: u8,
+
+  /// Switch for writing the selected strings without quotes.
+  #[arg(
+    default_value = "false",
+    help = "\
+Switch for writing the strings selected by <SELECTION> without quotes, like `jq -r`.
+It is disabled by default.
+",
+    long,
+    requires = "select",
+    short = 'r',
+  )]
+  pub raw_output: bool,
+
+  /// The patterns of the secrets to be redacted from the logs and the error messages.
+  #[arg(
+    help = "\
+The regex patterns of the secrets to be redacted from the logs and the error messages.
+The API keys, organization IDs and project IDs are always redacted.
+This option can be provided multiple times.
+",
+    long,
+    value_name = "PATTERN",
+  )]
+  pub redact: Vec<Regex>,
+
+  /// Switch for saving the metadata of the API request and response next to the output file.
+  #[arg(
+    default_value = "false",
+    help = "\
+Switch for saving the metadata of the API request and response in the file `<OUTPUT>.meta.json`
+next to the output file, which contains the final URL, the HTTP method, the status code, the request ID,
+the sources and the masked values of the credentials, the SHA-256 hash of the API request body,
+the response headers, the timings and the usage of the API response.
+The timings are `first_byte_ms` until the response headers arrive, which includes DNS lookup,
+connection and TLS handshake, and `total_ms` until the whole body is written. No other phases are recorded.
+It is ignored if the output is the standard output. It is disabled by default.
+",
+    long,
+  )]
+  pub save_meta: bool,
+
+  /// The selection query on the API response.
+  #[arg(
+    help = "\
+The selection query on the API response, which is a subset of jq and JSONPath,
+for example, `.choices[0].message.content`, `.data[].id` or `$.data[*].id`.
+The selected values are written one per line instead of the whole body in <FORMAT>,
+and it applies to the JSON body and to each data in the server-sent events.
+The steps are the field `.name`, `.\"name\"` or `[\"name\"]`, the index `[0]` or `[-1]`,
+and the iteration over the elements or the values `[]`, `[*]` or `.*`.
+",
+    long,
+    value_name = "SELECTION",
+  )]
+  pub select: Option<Selector>,
+
+  /// The file path where the OpenAPI specification is stored.
+  #[arg(
+    help = "\
+The file path where the OpenAPI specification is stored in YAML or JSON format.
+It drives the checks of <PATH>, <METHOD> and the schemas instead of the embedded specification,
+so that a newer or private specification can be used without rebuilding the program.
+The program will attempt the following steps to obtain the OpenAPI specification:
+ 1. Read the file from the provided path <SPEC_FILE_PATH>.
+ 2. Read the file from the path in the environment variable `OPENAI_SPEC`.
+ 3. Read the file from the path in the setting `OPENAI_SPEC=<path>` of the default files
+    in the following order: `openai.env`, `.openai_profile`, `.env`,
+    `~/openai.env`, `~/.openai_profile` or `~/.env`.
+ 4. Use the embedded specification.
+Exit the program with a non-zero return code if the file of step 1, 2 or 3 is invalid.
+",
+    long,
+    value_name = "SPEC_FILE_PATH",
+  )]
+  pub spec: Option<PathBuf>,
+
+  /// Switch for writing the API response to both the output file and the standard output.
   #[arg(
     default_value = "false",
     help = "\
-Switch for verbose logging mode. This mode is useful for debugging purposes.
+Switch for writing the API response to both the output file and the standard output,
+for example, to watch a stream while saving it. It is disabled by default.
+",
+    long,
+    requires = "output_file",
+  )]
+  pub tee: bool,
+
+  /// Switch for wire tracing mode.
+  #[arg(
+    default_value = "false",
+    help = "\
+Switch for wire tracing mode. This mode is useful for debugging the HTTP traffic.
+It logs the full request and response headers, the bodies and their sizes,
+and the timings of DNS lookup, TCP connection, TLS handshake, first byte and total.
+The API request is sent over a direct HTTP/1.1 connection in this mode, without the proxies or the redirects.
+The values of headers `Authorization`, `api-key` and `OpenAI-Organization`,
+and the key-shaped strings in bodies are redacted.
 It is disabled by default.
+",
+    long,
+  )]
+  pub trace: bool,
+
+  /// Switch for validating the API request parameters without sending the API request.
+  #[arg(
+    conflicts_with = "no_validate",
+    default_value = "false",
+    help = "\
+Switch for validating the API request parameters (body) without sending the API request.
+The API key is not required in this mode. It is disabled by default.
+",
+    long,
+  )]
+  pub validate_only: bool,
+
+  /// The variables of the parameter templates.
+  #[arg(
+    help = "\
+The variable to be filled into the placeholders `{{NAME}}` of the parameter files
+in the format of `NAME=VALUE`, which takes precedence over <VARS_FILE_PATH>.
+This option can be provided multiple times.
+",
+    long,
+    value_name = "VAR",
+    value_parser = parse_var,
+  )]
+  pub var: Vec<(String, String)>,
+
+  /// The file path where the variables of the parameter templates are stored.
+  #[arg(
+    help = "\
+The file path where the variables of the parameter templates are stored
+as an object in JSON, JSON5, TOML or YAML format.
+The placeholder which is the whole string is replaced with the value of any type, for example, a number.
+",
+    long,
+    value_name = "VARS_FILE_PATH",
+  )]
+  pub vars_file: Option<PathBuf>,
+
+  /// The verbosity of logging.
+  #[arg(
+    action = ArgAction::Count,
+    help = "\
+The verbosity of logging. It is useful for debugging purposes.
+Only the warnings and errors are logged by default.
+ 1. `-v` logs the debugging messages.
+ 2. `-vv` also traces the HTTP traffic like the option `--trace` does.
 ",
     long,
     short = 'v',
   )]
-  pub verbose: bool,
+  pub verbose: u8,
 }
 
 impl Entry {
-  /// Run the program.
-  pub async fn run(mut self) -> Result<()> {
-    let logger = tracing_subscriber::fmt()
-      .with_target(false)
-      .with_writer(stderr)
-      .without_time();
-    if self.verbose {
-      logger
-        .with_max_level(Level::DEBUG)
-        .with_file(true)
-        .with_line_number(true)
-        .init();
-    } else {
-      logger
-        .with_max_level(Level::WARN)
-        .init();
+  /// Create the logger with the logging options.
+  pub fn logger(&self) -> Logger {
+    Logger {
+      file: self.log_file.clone(),
+      filter: self.log_filter.clone(),
+      format: self.log_format,
+      redactor: self.redactor(),
+      verbosity: if self.trace {
+        2
+      } else {
+        self.verbose.min(2) as i8 - self.quiet.min(2) as i8
+      },
     }
+  }
 
-    let key = Key::fetch(&self)?;
-    let organization = Organization::fetch(&self).ok();
-    if organization.is_none() {
-      info!("Ignored the field `organization` for not being fetched successfully");
+  /// Create the redactor for the logs and the error messages.
+  pub fn redactor(&self) -> Redactor {
+    Redactor::new(self.redact.clone())
+  }
+
+  /// Returns the options of exporting the API response.
+  pub fn export_options(&self) -> ExportOptions {
+    ExportOptions {
+      format: self.format,
+      raw_output: self.raw_output,
+      select: self.select.clone(),
     }
-    let output = Output::fetch(&self)?;
-    // `parameter` should be fetched before `method`
-    let parameter = Parameter::fetch(&self).ok();
-    if parameter.is_none() {
-      info!("Ignored the field `parameter` for not being fetched successfully");
+  }
+
+  /// Returns the arguments for loaders.
+  pub fn arguments(&self) -> Arguments {
+    Arguments {
+      append: self.append,
+      check_response: self.check_response,
+      ignore_stdin: self.ignore_stdin,
+      items: self.items.clone(),
+      key_file: self.key_file.clone(),
+      method: self.method.clone(),
+      mkdir: self.mkdir,
+      no_validate: self.no_validate,
+      organization_file: self.organization_file.clone(),
+      output_dir: self.output_dir.clone(),
+      output_file: self.output_file.clone(),
+      output_name: self.output_name.clone(),
+      parameter: None,
+      parameter_files: self.parameter_file.clone(),
+      path: self.path.clone().unwrap_or_default(),
+      path_params: self.path_param.clone(),
+      save_meta: self.save_meta,
+      spec: None,
+      spec_file: self.spec.clone(),
+      tee: self.tee,
+      vars: self.var.clone(),
+      vars_file: self.vars_file.clone(),
     }
-    self._parameter = parameter;
-    let path = Path::fetch(&self)?;
-    let method = Method::fetch(&self)?;
-
-    let client = OpenAIClient::new(key, organization);
-    let request = OpenAIRequest::new(method, path, self._parameter)?;
-    let response = client.send(request).await?;
-    // debug!("\n{:#?}", response);
-
-    let status_error = response.error_for_status_ref().map(|_| ());
-    let content_type: Mime = response
-      .headers()
-      .get(CONTENT_TYPE)
-      .ok_or(Error::msg("The API response does not contain the header `Content-Type`"))?
-      .to_str()?
-      .parse()?;
-    info!("Resolving the API response in the content type: {content_type:?}");
-
-    let exporting_message = format!(
-      "Exporting the output to the {}",
-      if output.is_file() { "file" } else { "standard output" },
-    );
-
-    match content_type.subtype() {
-      mime::JSON => {
-        let response_json = response
-          .json::<serde_json::Value>()
-          .await
-          .map_err(Error::from)
-          .and_then(|object| {
-            serde_json::to_string_pretty(&object)
-              .map_err(Error::from)
-          });
-        if let Ok(response_json) = &response_json {
-          info!(
-            "Resolved the API response: <JSON Object ({} bytes)>",
-            response_json.len(),
-          );
-        }
-
-        if response_json.is_err() || status_error.is_err() {
-          Err(
-            Error::msg("\u{1b}[F")
-              .context(response_json.map_or_else(
-                |e| e.to_string(),
-                |json| format!("The API response in JSON format:\n{}", json)),
-              )
-              .context(status_error.map_or_else(
-                |e| e.to_string(),
-                |_| String::new(),
-              ))
-              .context("Failed to resolve the API response")
-          )
-        } else {
-          let response_json = response_json.unwrap();
-          let mut output = output.value();
-          info!("{}", exporting_message);
-          output.write_all(response_json.as_bytes())?;
-          Ok(())
-        }
+  }
+
+  /// Create the resolution context from the environment of the current process.
+  pub fn context(&self) -> Result<Context> {
+    Context::new(self.arguments())
+  }
+
+  /// Run the program as a library.
+  ///
+  /// It sends the API request with the arguments and resolves the API response.
+  /// Neither the logger is installed nor the output writer is used.
+  pub async fn run(self) -> Result<OpenAIResponse> {
+    self.context()?.run().await
+  }
+
+  /// Run the program as the CLI.
+  ///
+  /// It installs the logger, runs the program and exports the API response to the output writer.
+  /// If the subcommand is provided, it is run instead of sending the API request.
+  pub async fn run_cli(self) -> Result<()> {
+    self.logger().init()?;
+    let mut context = self.context()?;
+    context.args.spec = Some(Spec::fetch(&context)?);
+    match self.command {
+      Some(command) => {
+        let mut output = Output::fetch(&context)?;
+        command.run(&context, &mut output)?;
+        output.finish()
       },
-      mime::EVENT_STREAM => {
-        status_error?; // should not be an error
-
-        info!("{}", exporting_message);
-        let mut stream = response.bytes_stream().eventsource();
-        let mut output = output.value();
-        while let Some(chunk) = stream.next().await {
-          let chunk = chunk?;
-          let data = chunk.data;
-          info!(
-            "Resolved the API response: <Event Stream Data: ({} bytes)>",
-            data.len(),
-          );
-          if data == "[DONE]" {
-            info!("Reached the end of the API response");
-            break;
-          }
-          if chunk.retry.is_some() {
-            return Err(Error::msg("Failed to resolve API response: Retry occurred"));
-          }
-          output.write_all(&[data.as_bytes(), b"\n"].concat())?;
-        }
+      None if self.validate_only => {
+        // The output target is left untouched since no API response is exported.
+        let (path, method) = context.resolve()?;
+        let state = if context.args.parameter.is_some() { "valid" } else { "not provided" };
+        writeln!(
+          stdout(),
+          "The API request parameters are {state} for {} {}",
+          method.value_ref(), path.template(),
+        )?;
         Ok(())
       },
-      _ => Err(Error::msg(format!(
-        "Failed to resolve API response: {content_type:?} is an invalid format"
-      ))),
+      None => {
+        let output = Output::fetch(&context)?;
+        context.run().await?.export_with(output, &self.export_options()).await
+      },
     }
   }
 }
+
+fn parse_output_name(text: &str) -> Result<String> {
+  for name in Regex::new(r"\{([^{}]*)\}")?.captures_iter(text).map(|captures| captures[1].to_string()) {
+    if !Output::NAME_PLACEHOLDERS.contains(&name.as_str()) {
+      return Err(Error::msg(format!(
+        "The placeholder `{{{name}}}` is unknown, which should be one of {}",
+        Output::NAME_PLACEHOLDERS.map(|name| format!("`{{{name}}}`")).join(", "),
+      )));
+    }
+  }
+  Ok(text.to_string())
+}
+
+fn parse_path_param(text: &str) -> Result<(String, String)> {
+  let (name, value) = text
+    .split_once('=')
+    .ok_or(Error::msg("The path parameter should be in the format of `NAME=VALUE`"))?;
+  Ok((name.to_string(), value.to_string()))
+}
+
+fn parse_var(text: &str) -> Result<(String, String)> {
+  let (name, value) = text
+    .split_once('=')
+    .ok_or(Error::msg("The variable should be in the format of `NAME=VALUE`"))?;
+  Ok((name.to_string(), value.to_string()))
+}
@@ -0,0 +1,203 @@
+use crate::{escape_pointer, Context, Error, Result};
+use serde_json::{Map, Value};
+use std::{fmt, path::PathBuf, str::FromStr};
+
+/// The inline item of the API request parameters in the format of httpie,
+/// for example, `model=gpt-4`, `temperature:=0.2`, `messages[0].content=@prompt.txt` or `stop[]=END`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParameterItem {
+  /// The field path of the item, which addresses nested fields and array elements.
+  pub key: Vec<ItemKey>,
+
+  /// The original text of the item.
+  pub text: String,
+
+  /// The value of the item.
+  pub value: ItemValue,
+}
+
+/// The segment of the field path of an inline item.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ItemKey {
+  /// The empty brackets `[]`, which append an element to the array.
+  Append,
+
+  /// The array index in brackets, for example, `[0]`.
+  Index(usize),
+
+  /// The object field name.
+  Name(String),
+}
+
+/// The value of an inline item.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ItemValue {
+  /// The raw JSON value after `:=`.
+  Json(Value),
+
+  /// The file path after `:=@`, whose contents are read as a raw JSON value.
+  JsonFile(PathBuf),
+
+  /// The string after `=`.
+  String(String),
+
+  /// The file path after `=@`, whose contents are read as a string.
+  StringFile(PathBuf),
+}
+
+impl ParameterItem {
+  /// Set the item into the parameter object, creating the missing objects and arrays on the way,
+  /// and returns the JSON pointer of the field which is set.
+  ///
+  /// It fails if the field path runs into a value of another type or an array index out of range.
+  pub fn apply(&self, target: &mut Value, context: &Context) -> Result<String> {
+    let value = self.resolve(context)?;
+    let mut current = target;
+    let mut pointer = String::new();
+    for (depth, key) in self.key.iter().enumerate() {
+      let parent = || display_key(&self.key[..depth]);
+      current = match key {
+        ItemKey::Name(name) => {
+          if current.is_null() {
+            *current = Value::Object(Map::new());
+          }
+          let object = current
+            .as_object_mut()
+            .ok_or_else(|| self.conflict("an object", parent()))?;
+          pointer = format!("{pointer}/{}", escape_pointer(name));
+          object
+            .entry(name.clone())
+            .or_insert(Value::Null)
+        },
+        ItemKey::Index(index) => {
+          if current.is_null() {
+            *current = Value::Array(vec![]);
+          }
+          let array = current
+            .as_array_mut()
+            .ok_or_else(|| self.conflict("an array", parent()))?;
+          if *index == array.len() {
+            array.push(Value::Null);
+          }
+          let length = array.len();
+          pointer = format!("{pointer}/{index}");
+          array.get_mut(*index).ok_or_else(|| Error::msg(format!(
+            "The inline item `{}` has the index {index} out of range at `{}` of length {length}",
+            self.text, parent(),
+          )))?
+        },
+        ItemKey::Append => {
+          if current.is_null() {
+            *current = Value::Array(vec![]);
+          }
+          let array = current
+            .as_array_mut()
+            .ok_or_else(|| self.conflict("an array", parent()))?;
+          pointer = format!("{pointer}/{}", array.len());
+          array.push(Value::Null);
+          array.last_mut().unwrap()
+        },
+      };
+    }
+    *current = value;
+    Ok(pointer)
+  }
+
+  fn conflict(&self, expected: &str, parent: String) -> Error {
+    Error::msg(format!(
+      "The inline item `{}` expects {expected} at `{}`, which is another type of value",
+      self.text,
+      if parent.is_empty() { "the root" } else { &parent },
+    ))
+  }
+
+  fn resolve(&self, context: &Context) -> Result<Value> {
+    let read_error = |path: &PathBuf| {
+      let text = self.text.clone();
+      let path = path.clone();
+      move |err: Error| err.context(format!("Failed to read the file {path:?} of the inline item `{text}`"))
+    };
+    Ok(match &self.value {
+      ItemValue::Json(value) => value.clone(),
+      ItemValue::JsonFile(path) => context
+        .read(path)
+        .and_then(|bytes| Ok(serde_json::from_slice(&bytes)?))
+        .map_err(read_error(path))?,
+      ItemValue::String(text) => Value::String(text.clone()),
+      ItemValue::StringFile(path) => Value::String(context.read_to_string(path).map_err(read_error(path))?),
+    })
+  }
+}
+
+impl fmt::Display for ParameterItem {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str(&self.text)
+  }
+}
+
+impl FromStr for ParameterItem {
+  type Err = Error;
+
+  fn from_str(text: &str) -> Result<Self> {
+    let invalid = || Error::msg(format!(
+      "The inline item `{text}` should be in the format of `NAME=VALUE`, `NAME:=JSON`, \
+      `NAME=@FILE` or `NAME:=@FILE`",
+    ));
+    let (key, value) = text.split_once('=').ok_or_else(invalid)?;
+    let value = match (key.strip_suffix(':'), value.strip_prefix('@')) {
+      (Some(_), Some(path)) => ItemValue::JsonFile(path.into()),
+      (Some(_), None) => ItemValue::Json(serde_json::from_str(value).map_err(|err| {
+        Error::msg(format!("The inline item `{text}` has the invalid JSON value: {err}"))
+      })?),
+      (None, Some(path)) => ItemValue::StringFile(path.into()),
+      (None, None) => ItemValue::String(value.into()),
+    };
+    let key = key.strip_suffix(':').unwrap_or(key);
+    Ok(Self {
+      key: parse_key(key).ok_or_else(invalid)?,
+      text: text.into(),
+      value,
+    })
+  }
+}
+
+impl TryFrom<&str> for ParameterItem {
+  type Error = Error;
+
+  fn try_from(text: &str) -> Result<Self> {
+    Self::from_str(text)
+  }
+}
+
+fn display_key(key: &[ItemKey]) -> String {
+  key
+    .iter()
+    .enumerate()
+    .map(|(depth, key)| match key {
+      ItemKey::Append => "[]".to_string(),
+      ItemKey::Index(index) => format!("[{index}]"),
+      ItemKey::Name(name) if depth == 0 => name.clone(),
+      ItemKey::Name(name) => format!(".{name}"),
+    })
+    .collect()
+}
+
+fn parse_key(text: &str) -> Option<Vec<ItemKey>> {
+  let mut key = vec![];
+  for part in text.split('.') {
+    let (name, mut brackets) = part.split_at(part.find('[').unwrap_or(part.len()));
+    if name.is_empty() {
+      return None;
+    }
+    key.push(ItemKey::Name(name.into()));
+    while !brackets.is_empty() {
+      let (inner, rest) = brackets.strip_prefix('[')?.split_once(']')?;
+      key.push(match inner {
+        "" => ItemKey::Append,
+        index => ItemKey::Index(index.parse().ok()?),
+      });
+      brackets = rest;
+    }
+  }
+  Some(key)
+}
@@ -0,0 +1,181 @@
+use crate::{Context, ParameterItem, Result};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+
+/// The API request parameters merged from the layers in order, which remembers the source layer of each field.
+///
+/// The layers are merged with the semantics of JSON Merge Patch (RFC 7396),
+/// in which the objects are merged recursively, the fields of `null` are removed and the other values are replaced.
+/// The arrays are replaced unless the patch is `{"$append": [...]}`, which appends the elements instead.
+/// The patch `{"$replace": ...}` replaces the value explicitly without merging the objects.
+/// The fields of `null` in the first layer are kept as they are, since there is nothing to remove.
+#[derive(Clone, Debug, Default)]
+pub struct ParameterLayers {
+  sources: BTreeMap<String, String>,
+  value: Option<Value>,
+}
+
+impl ParameterLayers {
+  /// The key of the patch which appends the elements to the array.
+  pub const APPEND: &'static str = "$append";
+
+  /// The key of the patch which replaces the value without merging.
+  pub const REPLACE: &'static str = "$replace";
+
+  /// Merge the patch from the source layer on top of the merged value.
+  pub fn merge(&mut self, patch: Value, source: &str) {
+    let base = self.value.is_none();
+    let target = self.value.get_or_insert(Value::Null);
+    merge_at(target, patch, String::new(), source, &mut self.sources, base);
+  }
+
+  /// Set the inline item on top of the merged value.
+  pub fn set(&mut self, item: &ParameterItem, context: &Context) -> Result<()> {
+    let mut value = self.value.clone().unwrap_or_default();
+    let pointer = item.apply(&mut value, context)?;
+    self.value = Some(value);
+    clear(&mut self.sources, &pointer);
+    self.sources.insert(pointer, format!("the inline item `{item}`"));
+    Ok(())
+  }
+
+  /// Returns `true` if no layer is merged.
+  pub fn is_empty(&self) -> bool {
+    self.value.is_none()
+  }
+
+  /// Returns the source layer of the field at the JSON pointer, which is the latest layer setting it.
+  ///
+  /// The source of the root object is not inherited by the fields.
+  pub fn source(&self, pointer: &str) -> Option<&str> {
+    self.sources
+      .range(..=pointer.to_string())
+      .rev()
+      .find(|(prefix, _)| contains(prefix, pointer) && (prefix.is_empty() == pointer.is_empty()))
+      .map(|(_, source)| source.as_str())
+  }
+
+  /// Returns the JSON pointers, the values and the source layers of the leaf fields in order.
+  ///
+  /// The leaf fields are the values except the non-empty objects and arrays.
+  pub fn fields(&self) -> Vec<(String, &Value, &str)> {
+    let mut fields = vec![];
+    if let Some(value) = self.value.as_ref() {
+      self.collect(value, String::new(), &mut fields);
+    }
+    fields
+  }
+
+  /// Returns the mutable reference to the merged value if any layer is merged.
+  pub fn value_mut(&mut self) -> Option<&mut Value> {
+    self.value.as_mut()
+  }
+
+  /// Returns the merged value if any layer is merged.
+  pub fn value(self) -> Option<Value> {
+    self.value
+  }
+
+  /// Returns the reference to the merged value if any layer is merged.
+  pub fn value_ref(&self) -> Option<&Value> {
+    self.value.as_ref()
+  }
+
+  fn collect<'a>(&'a self, value: &'a Value, pointer: String, fields: &mut Vec<(String, &'a Value, &'a str)>) {
+    match value {
+      Value::Object(object) if !object.is_empty() => {
+        for (key, value) in object {
+          self.collect(value, format!("{pointer}/{}", escape_pointer(key)), fields);
+        }
+      },
+      Value::Array(array) if !array.is_empty() => {
+        for (index, value) in array.iter().enumerate() {
+          self.collect(value, format!("{pointer}/{index}"), fields);
+        }
+      },
+      value => {
+        let source = self.source(&pointer).unwrap_or_default();
+        fields.push((pointer, value, source));
+      },
+    }
+  }
+}
+
+/// Escape the object field name as a reference token of JSON pointer.
+pub fn escape_pointer(name: &str) -> String {
+  name.replace('~', "~0").replace('/', "~1")
+}
+
+/// Returns `true` if the JSON pointer `prefix` refers to `pointer` or its ancestor.
+fn contains(prefix: &str, pointer: &str) -> bool {
+  pointer
+    .strip_prefix(prefix)
+    .map_or(false, |rest| rest.is_empty() || rest.starts_with('/'))
+}
+
+/// Remove the sources of the field at the JSON pointer and its descendants.
+fn clear(sources: &mut BTreeMap<String, String>, pointer: &str) {
+  sources.retain(|prefix, _| !contains(pointer, prefix));
+}
+
+/// Returns the value of the patch `{"<keyword>": value}`.
+fn directive(patch: &mut Map<String, Value>, keyword: &str) -> Option<Value> {
+  (patch.len() == 1).then(|| patch.remove(keyword)).flatten()
+}
+
+fn merge_at(
+  target: &mut Value,
+  patch: Value,
+  pointer: String,
+  source: &str,
+  sources: &mut BTreeMap<String, String>,
+  base: bool,
+) {
+  let mut patch = match patch {
+    Value::Object(patch) => patch,
+    patch => {
+      clear(sources, &pointer);
+      sources.insert(pointer, source.to_string());
+      *target = patch;
+      return;
+    },
+  };
+  if let Some(elements) = directive(&mut patch, ParameterLayers::APPEND) {
+    if !target.is_array() {
+      clear(sources, &pointer);
+      *target = Value::Array(vec![]);
+    }
+    let array = target.as_array_mut().unwrap();
+    let elements = match elements {
+      Value::Array(elements) => elements,
+      element => vec![element],
+    };
+    for element in elements {
+      sources.insert(format!("{pointer}/{}", array.len()), source.to_string());
+      array.push(element);
+    }
+    return;
+  }
+  if let Some(value) = directive(&mut patch, ParameterLayers::REPLACE) {
+    clear(sources, &pointer);
+    sources.insert(pointer, source.to_string());
+    *target = value;
+    return;
+  }
+
+  if !target.is_object() {
+    clear(sources, &pointer);
+    sources.insert(pointer.clone(), source.to_string());
+    *target = Value::Object(Map::new());
+  }
+  let object = target.as_object_mut().unwrap();
+  for (key, value) in patch {
+    let pointer = format!("{pointer}/{}", escape_pointer(&key));
+    if value.is_null() && !base {
+      object.retain(|name, _| *name != key);
+      clear(sources, &pointer);
+    } else {
+      merge_at(object.entry(key).or_insert(Value::Null), value, pointer, source, sources, base);
+    }
+  }
+}
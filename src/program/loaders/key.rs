@@ -1,17 +1,28 @@
-use crate::{Entry, Error, Result, traits::*};
+use crate::{Context, Error, Result, redaction::mask, traits::*};
 use regex::Regex;
-use shellexpand::path::tilde;
-use std::{env, fs, path::{Path, PathBuf}, str::FromStr};
+use std::{fs, path::{Path, PathBuf}, str::FromStr};
 use tracing::{debug, info};
 
 /// The API key.
-pub struct Key(String);
+pub struct Key {
+  /// The source where the API key is fetched from, which is set on fetching.
+  source: Option<String>,
+
+  /// The API key.
+  value: String,
+}
 
 impl Key {
-  fn post_fetch_ok(self, source: &str) -> Result<Self> {
+  /// Returns the source where the API key is fetched from, for example, `the environment variable ...`.
+  pub fn source(&self) -> Option<&str> {
+    self.source.as_deref()
+  }
+
+  fn post_fetch_ok(mut self, source: &str) -> Result<Self> {
+    self.source = Some(source.to_string());
     info!(
       "Successfully fetched the API key from {source}: {:?}",
-      format!("{}...{}", &self.value_ref()[..6], &self.value_ref()[49..]),
+      mask(self.value_ref()),
     );
     Ok(self)
   }
@@ -31,30 +42,29 @@ impl FromStr for Key {
   type Err = Error;
 
   fn from_str(text: &str) -> Result<Self> {
-    Ok(Self(
-      Regex::new(r"sk-[[:alnum:]]{20}T3BlbkFJ[[:alnum:]]{20}")?
+    Ok(Self {
+      source: None,
+      value: Regex::new(r"sk-[[:alnum:]]{20}T3BlbkFJ[[:alnum:]]{20}")?
         .find(text)
         .ok_or(Error::msg("Invalid format of OpenAI API key"))?
         .as_str()
-        .to_string()
-    ))
+        .to_string(),
+    })
   }
 }
 
 impl Loader<String> for Key {
-  fn fetch(entry: &Entry) -> Result<Self> {
-    if let Some(provided_file) = entry.key_file.as_ref() {
+  fn fetch(context: &Context) -> Result<Self> {
+    if let Some(provided_file) = context.args.key_file.as_ref() {
       let source = &format!("the provided file {provided_file:?}");
-      match Key::from_file(provided_file) {
+      match context.read_to_string(provided_file).and_then(Key::try_from) {
         Ok(key) => return key.post_fetch_ok(source),
         Err(err) => debug!("Failed to obtain the API key from {source}: {err:?}"),
       }
     }
 
     let source = "the environment variable `OPENAI_API_KEY`";
-    match env::var("OPENAI_API_KEY")
-      .map_err(Error::from)
-      .and_then(Key::try_from)
+    match context.var("OPENAI_API_KEY").and_then(Key::try_from)
     {
       Ok(key) => return key.post_fetch_ok(source),
       Err(err) => debug!("Failed to obtain the API key from {source}: {err:?}"),
@@ -64,13 +74,13 @@ impl Loader<String> for Key {
         &PathBuf::from("openai.env"),
         &PathBuf::from(".openai_profile"),
         &PathBuf::from(".env"),
-        &PathBuf::from(tilde("~/openai.env")),
-        &PathBuf::from(tilde("~/.openai_profile")),
-        &PathBuf::from(tilde("~/.env")),
+        &PathBuf::from("~/openai.env"),
+        &PathBuf::from("~/.openai_profile"),
+        &PathBuf::from("~/.env"),
       ].into_iter()
     {
       let source = &format!("the default file {default_file:?}");
-      match Key::from_file(default_file) {
+      match context.read_to_string(default_file).and_then(Key::try_from) {
         Ok(key) => return key.post_fetch_ok(source),
         Err(err) => debug!("Failed to obtain the API key from {source}: {err:?}"),
       }
@@ -78,10 +88,10 @@ impl Loader<String> for Key {
     Err(Error::msg("Failed to fetch the API key"))
   }
   fn value(self) -> String {
-    self.0
+    self.value
   }
   fn value_ref(&self) -> &String {
-    &self.0
+    &self.value
   }
 }
 
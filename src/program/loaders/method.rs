@@ -1,6 +1,6 @@
-use crate::{Entry, Error, Result, traits::*};
+use crate::{Context, Endpoint, Error, Path, Result, traits::*};
 use std::str::FromStr;
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 
 /// The HTTP method for the API request.
 pub struct Method(http::Method);
@@ -12,7 +12,8 @@ impl Method {
   pub const DELETE: Method = Method(http::Method::DELETE);
   /// GET method.
   /// 
-  /// If the API request parameters (body) are not provided, this method is preferred.
+  /// If the API request parameters (body) are not provided, this method is preferred
+  /// among the allowed methods.
   pub const GET: Method = Method(http::Method::GET);
   /// HEAD method.
   pub const HEAD: Method = Method(http::Method::HEAD);
@@ -21,6 +22,9 @@ impl Method {
   /// PATCH method.
   pub const PATCH: Method = Method(http::Method::PATCH);
   /// POST method.
+  ///
+  /// If the API request parameters (body) are provided, this method is preferred
+  /// among the allowed methods.
   pub const POST: Method = Method(http::Method::POST);
   /// PUT method.
   pub const PUT: Method = Method(http::Method::PUT);
@@ -45,25 +49,58 @@ impl FromStr for Method {
 }
 
 impl Loader<http::Method> for Method {
-  fn fetch(entry: &Entry) -> Result<Self> {
+  fn fetch(context: &Context) -> Result<Self> {
+    let endpoint = Path::resolve(context)
+      .ok()
+      .and_then(|path| context.catalog().endpoint(path.template()));
+    let has_parameter = context.args.parameter.is_some();
+
     let source_ok = "the program arguments";
-    match entry.method
+    let method = match context.args.method
       .as_ref()
       .ok_or(Error::msg("Not provided"))
       .and_then(Method::try_from)
     {
-      Ok(method) => method.post_fetch_ok(source_ok),
+      Ok(method) => {
+        if let Some(endpoint) = endpoint {
+          if endpoint.operation(method.value_ref().as_str()).is_none() {
+            return Err(Error::msg(format!(
+              "The API request method {} is not allowed on the path {:?}, the allowed methods are: {}",
+              method.value_ref(), endpoint.path, endpoint.methods().join(", "),
+            )));
+          }
+        }
+        method.post_fetch_ok(source_ok)?
+      },
       Err(err) => {
         debug!("Failed to obtain the API request method from {source_ok}: {err:?}");
-        let (method, dep_status) = if entry._parameter.is_some() {
-          (Self::POST, "")
-        } else {
-          (Self::GET, "un")
-        };
+        let dep_status = if has_parameter { "" } else { "un" };
         debug!("The API request parameters were fetched {dep_status}successfully");
-        method.post_fetch_ok("the fallback options")
+        match endpoint.map(Endpoint::methods).as_deref() {
+          Some([method]) => Method::from_str(method)?
+            .post_fetch_ok("the only operation in the OpenAPI specification")?,
+          Some(methods) if !methods.is_empty() => {
+            let preferred = if has_parameter { "POST" } else { "GET" };
+            Method::from_str(
+              methods
+                .iter()
+                .find(|method| **method == preferred)
+                .unwrap_or(&methods[0])
+            )?
+            .post_fetch_ok("the operations in the OpenAPI specification")?
+          },
+          _ => {
+            let method = if has_parameter { Self::POST } else { Self::GET };
+            method.post_fetch_ok("the fallback options")?
+          },
+        }
       },
+    };
+
+    if method.0 == http::Method::GET && has_parameter {
+      warn!("The API request parameters (body) will be sent with the method GET");
     }
+    Ok(method)
   }
   fn value(self) -> http::Method {
     self.0
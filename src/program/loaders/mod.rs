@@ -21,3 +21,7 @@ pub use parameter::*;
 /// Path loader
 pub mod path;
 pub use path::*;
+
+/// OpenAPI specification loader
+pub mod spec;
+pub use spec::*;
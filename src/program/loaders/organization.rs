@@ -1,17 +1,28 @@
-use crate::{Entry, Error, Result, traits::*};
+use crate::{Context, Error, Result, redaction::mask, traits::*};
 use regex::Regex;
-use shellexpand::path::tilde;
-use std::{env, fs, path::{Path, PathBuf}, str::FromStr};
+use std::{fs, path::{Path, PathBuf}, str::FromStr};
 use tracing::{debug, info};
 
 /// The organization ID.
-pub struct Organization(String);
+pub struct Organization {
+  /// The source where the organization ID is fetched from, which is set on fetching.
+  source: Option<String>,
+
+  /// The organization ID.
+  value: String,
+}
 
 impl Organization {
-  fn post_fetch_ok(self, source: &str) -> Result<Self> {
+  /// Returns the source where the organization ID is fetched from, for example, `the environment variable ...`.
+  pub fn source(&self) -> Option<&str> {
+    self.source.as_deref()
+  }
+
+  fn post_fetch_ok(mut self, source: &str) -> Result<Self> {
+    self.source = Some(source.to_string());
     info!(
       "Successfully fetched the organization ID from {source}: {:?}",
-      format!("{}...{}", &self.value_ref()[..7], &self.value_ref()[26..]),
+      mask(self.value_ref()),
     );
     Ok(self)
   }
@@ -31,36 +42,35 @@ impl FromStr for Organization {
   type Err = Error;
 
   fn from_str(text: &str) -> Result<Self> {
-    Ok(Self(
-      Regex::new(r"org-[[:alnum:]]{24}")?
+    Ok(Self {
+      source: None,
+      value: Regex::new(r"org-[[:alnum:]]{24}")?
         .find(text)
         .ok_or(Error::msg("Invalid format of OpenAI organization ID"))?
         .as_str()
-        .to_string()
-    ))
+        .to_string(),
+    })
   }
 }
 
 impl Loader<String> for Organization {
-  fn fetch(entry: &Entry) -> Result<Self> {
+  fn fetch(context: &Context) -> Result<Self> {
     for provided_file in [
-        entry.organization_file.as_ref(),
-        entry.key_file.as_ref(),
+        context.args.organization_file.as_ref(),
+        context.args.key_file.as_ref(),
       ]
       .into_iter()
       .flatten()
     {
       let source = &format!("the provided file {provided_file:?}");
-      match Organization::from_file(provided_file) {
+      match context.read_to_string(provided_file).and_then(Organization::try_from) {
         Ok(organization) => return organization.post_fetch_ok(source),
         Err(err) => debug!("Failed to obtain the organization ID from {source}: {err:?}"),
       }
     }
 
     let source = "the environment variable `OPENAI_ORG_KEY`";
-    match env::var("OPENAI_ORG_KEY")
-      .map_err(Error::from)
-      .and_then(Organization::try_from)
+    match context.var("OPENAI_ORG_KEY").and_then(Organization::try_from)
     {
       Ok(organization) => return organization.post_fetch_ok(source),
       Err(err) => debug!("Failed to obtain the organization ID from {source}: {err:?}"),
@@ -70,13 +80,13 @@ impl Loader<String> for Organization {
         &PathBuf::from("openai.env"),
         &PathBuf::from(".openai_profile"),
         &PathBuf::from(".env"),
-        &PathBuf::from(tilde("~/openai.env")),
-        &PathBuf::from(tilde("~/.openai_profile")),
-        &PathBuf::from(tilde("~/.env")),
+        &PathBuf::from("~/openai.env"),
+        &PathBuf::from("~/.openai_profile"),
+        &PathBuf::from("~/.env"),
       ].into_iter()
     {
       let source = &format!("the default file {default_file:?}");
-      match Organization::from_file(default_file) {
+      match context.read_to_string(default_file).and_then(Organization::try_from) {
         Ok(organization) => return organization.post_fetch_ok(source),
         Err(err) => debug!("Failed to obtain the organization ID from {source}: {err:?}"),
       }
@@ -84,10 +94,10 @@ impl Loader<String> for Organization {
     Err(Error::msg("Failed to fetch the organization ID"))
   }
   fn value(self) -> String {
-    self.0
+    self.value
   }
   fn value_ref(&self) -> &String {
-    &self.0
+    &self.value
   }
 }
 
@@ -1,14 +1,242 @@
-use crate::{Entry, Result, traits::*};
-use std::{fs::File, path::Path, io::{stdout, Write}};
+use crate::{Arguments, Context, ExportOptions, OpenAIResponse, OsFileSystem, Result, redaction::redact_header, traits::*};
+use http::header::CONTENT_TYPE;
+use mime::Mime;
+use serde_json::{json, Map, Value};
+use std::{
+  io::{self, stdout, Write},
+  path::{Path, PathBuf},
+  process,
+  sync::Arc,
+  time::{Instant, SystemTime, UNIX_EPOCH},
+};
 use tracing::{debug, info};
 
 /// The output writer.
-pub struct Output(Box<dyn Write>, bool);
+///
+/// The output file is written through a temporary file in the same directory,
+/// which replaces the file only when the output is finished,
+/// so a failed API request neither truncates the existing file nor leaves a half-written one.
+/// The output is appended to the file directly if the option `--append` is provided.
+///
+/// In the output directory mode, the file is named by the template after the API response is exported,
+/// and the index of the files is appended to the file `index.jsonl` in the directory.
+///
+/// The metadata of the API request and response is saved in the sidecar file `<output>.meta.json`
+/// if the option `--save-meta` is provided.
+pub struct Output {
+  directory: Option<OutputDirectory>,
+  file: Option<OutputFile>,
+  meta: Option<OutputMeta>,
+  save_meta: bool,
+  tee: bool,
+  writer: Box<dyn Write>,
+}
+
+/// The template of the file names and the API request and response which name the file in the output directory.
+struct OutputDirectory {
+  ext: String,
+  method: Option<String>,
+  name: String,
+  parameter: Option<Value>,
+  path: String,
+  request_id: Option<String>,
+  status: Option<u16>,
+}
+
+/// The metadata of the API request and response, which is completed when the output is finished.
+struct OutputMeta {
+  fields: Map<String, Value>,
+  started: Instant,
+}
+
+/// The output file and the temporary file where it is written until the output is finished.
+struct OutputFile {
+  fs: Arc<dyn FileSystem>,
+  path: PathBuf,
+  temp_path: Option<PathBuf>,
+}
 
 impl Output {
+  /// The default template of the file names in the output directory.
+  pub const DEFAULT_NAME: &'static str = "{timestamp}-{path}-{request_id}.{ext}";
+
+  /// The name of the index file in the output directory.
+  pub const INDEX: &'static str = "index.jsonl";
+
+  /// The suffix of the sidecar file of the metadata, which is appended to the path of the output file.
+  pub const META_SUFFIX: &'static str = ".meta.json";
+
+  /// The placeholders of the template of the file names in the output directory.
+  pub const NAME_PLACEHOLDERS: [&'static str; 5] = ["ext", "method", "path", "request_id", "timestamp"];
+
   /// Check if the output writer is a file; otherwise, it is the standard output.
   pub fn is_file(&self) -> bool {
-    self.1
+    self.file.is_some()
+  }
+
+  /// Returns the path of the output file if it is a file.
+  pub fn path(&self) -> Option<&Path> {
+    self.file.as_ref().map(|file| file.path.as_path())
+  }
+
+  /// Record the API request and response, which name the file in the output directory mode
+  /// and make up the metadata if it is saved.
+  ///
+  /// The file extension follows the export options, or the content type of the response otherwise.
+  pub fn describe(&mut self, response: &OpenAIResponse, options: &ExportOptions) {
+    let request_id = response.headers
+      .get("x-request-id")
+      .and_then(|value| value.to_str().ok())
+      .map(ToString::to_string);
+    if self.save_meta && self.is_file() {
+      let headers: Map<_, _> = response.headers
+        .iter()
+        .map(|(name, value)| (name.to_string(), Value::String(redact_header(name, value))))
+        .collect();
+      let fields = json!({
+        "url": response.url.as_str(),
+        "method": response.method.as_ref().map(ToString::to_string),
+        "status": response.status.as_u16(),
+        "request_id": request_id,
+        "credentials": response.credentials,
+        "request_body_sha256": response.request_hash,
+        "headers": headers,
+        // Only the first byte and the total are measured, in which DNS lookup, connection and TLS handshake are included.
+        "timings": {
+          "first_byte_ms": response.first_byte.as_millis() as u64,
+        },
+        "usage": null,
+      });
+      self.meta = Some(OutputMeta {
+        fields: fields.as_object().cloned().unwrap_or_default(),
+        started: response.started,
+      });
+    }
+
+    let Some(directory) = self.directory.as_mut() else {
+      return;
+    };
+    let content_type = response.headers
+      .get(CONTENT_TYPE)
+      .and_then(|value| value.to_str().ok())
+      .and_then(|value| value.parse::<Mime>().ok());
+    directory.ext = match (options.extension(), content_type) {
+      (Some(ext), _) => ext.to_string(),
+      (None, Some(content_type)) if content_type.subtype() == mime::EVENT_STREAM => "jsonl".to_string(),
+      (None, Some(content_type)) => content_type.subtype().to_string(),
+      (None, None) => "txt".to_string(),
+    };
+    directory.method = response.method.as_ref().map(ToString::to_string);
+    directory.parameter = response.parameter.clone();
+    directory.path = response.url.path().trim_start_matches("/v1/").to_string();
+    directory.request_id = request_id;
+    directory.status = Some(response.status.as_u16());
+  }
+
+  /// Record the usage of the API response from the JSON body or the data of the server-sent events,
+  /// which is kept in the metadata if it is saved.
+  pub fn record_usage(&mut self, data: &[u8]) {
+    let Some(meta) = self.meta.as_mut() else {
+      return;
+    };
+    let usage = serde_json::from_slice::<Value>(data)
+      .ok()
+      .and_then(|mut value| value.get_mut("usage").map(Value::take))
+      .filter(|usage| !usage.is_null());
+    if let Some(usage) = usage {
+      meta.fields.insert("usage".to_string(), usage);
+    }
+  }
+
+  /// Flush the output and move the temporary file to the output file.
+  ///
+  /// In the output directory mode, the file is named by the template and recorded in the index file.
+  /// The metadata is saved next to the output file if it is recorded.
+  /// The temporary file is removed instead if the output is dropped without being finished,
+  /// while the writer moved out by `Loader::value` finishes the output when it is dropped.
+  pub fn finish(mut self) -> Result<()> {
+    self.flush()?;
+    self.writer = Box::new(io::sink());
+    let Some(mut file) = self.file.take() else {
+      return Ok(());
+    };
+    let fs = file.fs.clone();
+    let path = match self.directory.take() {
+      Some(directory) => {
+        let index = file.path.join(Self::INDEX);
+        let timestamp = format_timestamp(SystemTime::now());
+        let name = directory.unique_name(&*fs, &file.path, &timestamp);
+        file.path = file.path.join(&name);
+        info!("Storing the output in the file {:?}", file.path);
+        let path = file.path.clone();
+        file.commit()?;
+        writeln!(fs.append(&index)?, "{}", directory.index_entry(&name, &timestamp))?;
+        path
+      },
+      None => {
+        let path = file.path.clone();
+        file.commit()?;
+        path
+      },
+    };
+
+    if let Some(mut meta) = self.meta.take() {
+      let total = meta.started.elapsed().as_millis() as u64;
+      if let Some(Value::Object(timings)) = meta.fields.get_mut("timings") {
+        timings.insert("total_ms".to_string(), total.into());
+      }
+      let mut meta_path = path.into_os_string();
+      meta_path.push(Self::META_SUFFIX);
+      let meta_path = PathBuf::from(meta_path);
+      fs.create(&meta_path)?.write_all(serde_json::to_string_pretty(&meta.fields)?.as_bytes())?;
+      info!("Saved the metadata of the API response in the file {meta_path:?}");
+    }
+    Ok(())
+  }
+
+  fn open(fs: Arc<dyn FileSystem>, path: PathBuf, args: &Arguments) -> Result<Self> {
+    if args.mkdir {
+      if let Some(parent) = path.parent() {
+        fs.create_dir_all(parent)?;
+      }
+    }
+    let (writer, temp_path) = if args.append {
+      (fs.append(&path)?, None)
+    } else {
+      let temp_path = temp_path(&path);
+      (fs.create(&temp_path)?, Some(temp_path))
+    };
+    Ok(Self {
+      directory: None,
+      file: Some(OutputFile { fs, path, temp_path }),
+      meta: None,
+      save_meta: args.save_meta,
+      tee: args.tee,
+      writer,
+    })
+  }
+
+  fn open_dir(fs: Arc<dyn FileSystem>, dir: PathBuf, args: &Arguments) -> Result<Self> {
+    fs.create_dir_all(&dir)?;
+    let temp_path = dir.join(format!(".output.{}.tmp", process::id()));
+    let writer = fs.create(&temp_path)?;
+    let directory = OutputDirectory {
+      ext: "txt".to_string(),
+      method: None,
+      name: args.output_name.clone().unwrap_or(Self::DEFAULT_NAME.to_string()),
+      parameter: None,
+      path: args.path.trim_start_matches('/').to_string(),
+      request_id: None,
+      status: None,
+    };
+    Ok(Self {
+      directory: Some(directory),
+      file: Some(OutputFile { fs, path: dir, temp_path: Some(temp_path) }),
+      meta: None,
+      save_meta: args.save_meta,
+      tee: args.tee,
+      writer,
+    })
   }
 
   fn post_fetch_ok(self, target: &str) -> Result<Self> {
@@ -17,30 +245,154 @@ impl Output {
   }
 }
 
+impl OutputDirectory {
+  fn file_name(&self, timestamp: &str) -> String {
+    let sanitize = |text: &str| text.replace(|char: char| !(char.is_alphanumeric() || "-_.".contains(char)), "-");
+    self.name
+      .replace("{ext}", &self.ext)
+      .replace("{method}", &self.method.as_deref().unwrap_or("none").to_lowercase())
+      .replace("{path}", &sanitize(if self.path.is_empty() { "none" } else { &self.path }))
+      .replace("{request_id}", &sanitize(self.request_id.as_deref().unwrap_or("none")))
+      .replace("{timestamp}", timestamp)
+  }
+
+  /// Returns the file name which does not exist in the directory yet,
+  /// in which a counter is appended to the stem on collision, for example, `abc-1.json`.
+  fn unique_name(&self, fs: &dyn FileSystem, dir: &Path, timestamp: &str) -> String {
+    let name = self.file_name(timestamp);
+    if !fs.exists(&dir.join(&name)) {
+      return name;
+    }
+    let (stem, ext) = match name.rsplit_once('.') {
+      Some((stem, ext)) if !stem.is_empty() => (stem, format!(".{ext}")),
+      _ => (name.as_str(), String::new()),
+    };
+    (1..)
+      .map(|count| format!("{stem}-{count}{ext}"))
+      .find(|name| !fs.exists(&dir.join(name)))
+      .unwrap_or(name)
+  }
+
+  fn index_entry(&self, name: &str, timestamp: &str) -> Value {
+    json!({
+      "file": name,
+      "timestamp": timestamp,
+      "method": self.method,
+      "path": self.path,
+      "status": self.status,
+      "request_id": self.request_id,
+      "parameter": self.parameter,
+    })
+  }
+}
+
+impl OutputFile {
+  fn commit(mut self) -> io::Result<()> {
+    if let Some(temp_path) = self.temp_path.as_ref() {
+      self.fs.rename(temp_path, &self.path)?;
+    }
+    self.temp_path = None;
+    Ok(())
+  }
+}
+
+impl Drop for OutputFile {
+  fn drop(&mut self) {
+    if let Some(temp_path) = self.temp_path.take() {
+      if let Err(err) = self.fs.remove_file(&temp_path) {
+        debug!("Failed to remove the temporary output file {temp_path:?}: {err:?}");
+      }
+    }
+  }
+}
+
 impl FromFile for Output {
   fn from_file<P>(path: P) -> Result<Self>
   where
     P: AsRef<Path>,
   {
-    Ok(Self(Box::new(File::create(path)?), true))
+    Self::open(Arc::new(OsFileSystem), path.as_ref().to_path_buf(), &Arguments::default())
   }
 }
 
 impl Loader<Box<dyn Write>> for Output {
-  fn fetch(entry: &Entry) -> Result<Self> {
-    if let Some(path) = entry.output_file.as_ref() {
+  fn fetch(context: &Context) -> Result<Self> {
+    if let Some(dir) = context.args.output_dir.as_ref() {
+      let target = &format!("the directory {dir:?}");
+      match Self::open_dir(context.fs.clone(), context.resolve_path(dir), &context.args) {
+        Ok(output) => return output.post_fetch_ok(target),
+        Err(err) => debug!("Failed to create the output writer to {target}: {err:?}"),
+      }
+    }
+    if let Some(path) = context.args.output_file.as_ref() {
       let target = &format!("the file {path:?}");
-      match Output::from_file(path) {
+      match Self::open(context.fs.clone(), context.resolve_path(path), &context.args) {
         Ok(output) => return output.post_fetch_ok(target),
         Err(err) => debug!("Failed to create the output writer to {target}: {err:?}"),
       }
     }
-    Self(Box::new(stdout()), false).post_fetch_ok("the standard output")
+    if context.args.save_meta {
+      info!("Ignored the option `--save-meta` for exporting the output to the standard output");
+    }
+    let output = Self {
+      directory: None,
+      file: None,
+      meta: None,
+      save_meta: false,
+      tee: false,
+      writer: Box::new(stdout()),
+    };
+    output.post_fetch_ok("the standard output")
   }
+  /// Move the output out as a writer, which discards the temporary file when it is dropped.
+  ///
+  /// Keep the output object and call [`Output::finish`] instead to replace the output file.
   fn value(self) -> Box<dyn Write> {
-    self.0
+    Box::new(self)
   }
   fn value_ref(&self) -> &Box<dyn Write> {
-    &self.0
+    &self.writer
   }
 }
+
+impl Write for Output {
+  fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+    let size = self.writer.write(buf)?;
+    if self.tee {
+      stdout().write_all(&buf[..size])?;
+    }
+    Ok(size)
+  }
+  fn flush(&mut self) -> io::Result<()> {
+    self.writer.flush()?;
+    if self.tee {
+      stdout().flush()?;
+    }
+    Ok(())
+  }
+}
+
+/// Returns the path of the hidden temporary file next to the output file.
+fn temp_path(path: &Path) -> PathBuf {
+  let name = path.file_name().unwrap_or_default().to_string_lossy();
+  path.with_file_name(format!(".{name}.{}.tmp", process::id()))
+}
+
+/// Returns the UTC time in the basic format of ISO 8601 with milliseconds, for example, `20240101T120000.123Z`.
+fn format_timestamp(time: SystemTime) -> String {
+  let duration = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+  let (days, seconds) = (duration.as_secs() / 86400, duration.as_secs() % 86400);
+  // The civil date from the days since 1970-01-01 by the algorithm of Howard Hinnant.
+  let days = days + 719468;
+  let (era, day_of_era) = (days / 146097, days % 146097);
+  let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+  let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+  let month = (5 * day_of_year + 2) / 153;
+  let day = day_of_year - (153 * month + 2) / 5 + 1;
+  let month = if month < 10 { month + 3 } else { month - 9 };
+  let year = era * 400 + year_of_era + u64::from(month <= 2);
+  format!(
+    "{year:04}{month:02}{day:02}T{:02}{:02}{:02}.{:03}Z",
+    seconds / 3600, seconds / 60 % 60, seconds % 60, duration.subsec_millis(),
+  )
+}
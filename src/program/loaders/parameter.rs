@@ -1,7 +1,75 @@
-use crate::{Entry, Error, Result, traits::*};
+use crate::{
+  deprecated_model, Catalog, Context, Error, Method, ParameterLayers, ParameterTemplate, Result, Schema, loaders,
+  traits::*,
+};
 use serde::{Deserialize, Serialize};
-use std::{fs::File, path::Path, str::FromStr};
-use tracing::{debug, info};
+use std::{fs, io::{self, BufRead, BufReader, Read}, path::{Component, Path, PathBuf}, str::FromStr};
+use tracing::{debug, info, warn};
+
+/// The format of the parameter file.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ParameterFormat {
+  /// JSON.
+  Json,
+
+  /// JSON5, which is also used for JSONC (JSON with comments).
+  Json5,
+
+  /// TOML.
+  Toml,
+
+  /// YAML.
+  Yaml,
+}
+
+impl ParameterFormat {
+  /// The formats which are tried in order if the format is not chosen by the file extension.
+  pub const SNIFFING_ORDER: [Self; 4] = [Self::Json, Self::Json5, Self::Toml, Self::Yaml];
+
+  /// Returns the format chosen by the file extension,
+  /// which is one of `.json`, `.json5`, `.jsonc`, `.toml`, `.yaml` and `.yml`.
+  pub fn from_path(path: &Path) -> Option<Self> {
+    match path.extension()?.to_str()?.to_ascii_lowercase().as_str() {
+      "json" => Some(Self::Json),
+      "json5" | "jsonc" => Some(Self::Json5),
+      "toml" => Some(Self::Toml),
+      "yaml" | "yml" => Some(Self::Yaml),
+      _ => None,
+    }
+  }
+
+  /// Parse bytes in the format into a JSON value.
+  pub fn parse(self, slice: &[u8]) -> Result<serde_json::Value> {
+    Ok(match self {
+      Self::Json => serde_json::from_slice(slice)?,
+      Self::Json5 => json5::from_str(std::str::from_utf8(slice)?)?,
+      Self::Toml => toml::from_str(std::str::from_utf8(slice)?)?,
+      Self::Yaml => serde_yaml::from_slice(slice)?,
+    })
+  }
+
+  /// Parse bytes in the format chosen by the file extension,
+  /// or in the first format of [`ParameterFormat::SNIFFING_ORDER`] which results in an object.
+  pub fn parse_file(path: &Path, slice: &[u8]) -> Result<serde_json::Value> {
+    if let Some(format) = Self::from_path(path) {
+      return format.parse(slice);
+    }
+    Self::sniff(slice, &format!("the file {path:?}"))
+  }
+
+  /// Parse bytes in the first format of [`ParameterFormat::SNIFFING_ORDER`] which results in an object.
+  ///
+  /// It fails with the source in the message if none of the formats results in an object.
+  pub fn sniff(slice: &[u8], source: &str) -> Result<serde_json::Value> {
+    Self::SNIFFING_ORDER
+      .into_iter()
+      .find_map(|format| format.parse(slice).ok().filter(serde_json::Value::is_object).map(|value| {
+        debug!("Sniffed the format of {source}: {format:?}");
+        value
+      }))
+      .ok_or(Error::msg(format!("{source} is not an object in JSON, JSON5, TOML or YAML format")))
+  }
+}
 
 /// The API request parameters.
 #[derive(Clone, Deserialize, Serialize)]
@@ -9,11 +77,299 @@ use tracing::{debug, info};
 pub struct Parameter(serde_json::Value);
 
 impl Parameter {
+  /// The default file paths where the API request parameters are searched in order.
+  pub const DEFAULT_PATHS: [&'static str; 6] = [
+    "openai.json",
+    "openai-parameters.json",
+    "openai_parameters.json",
+    "openai-parameters",
+    "openai_parameters",
+    "openai.config.json",
+  ];
+
   /// Create a new parameter object from bytes of JSON.
   pub fn from_slice(slice: &[u8]) -> Result<Self> {
     Ok(serde_json::from_slice(slice)?)
   }
 
+  /// Create a new parameter object from bytes of the file,
+  /// whose format is chosen by the file extension or sniffed, see [`ParameterFormat::parse_file`].
+  pub fn from_file_slice(path: &Path, slice: &[u8]) -> Result<Self> {
+    Ok(Self(ParameterFormat::parse_file(path, slice)?))
+  }
+
+  /// Create a new parameter object from a reader of JSON, which is streamed without buffering the whole text.
+  pub fn from_reader<R>(reader: R) -> Result<Self>
+  where
+    R: Read,
+  {
+    Ok(serde_json::from_reader(BufReader::new(reader))?)
+  }
+
+  /// The field of the base parameter files, which are merged under the parameter object in order.
+  ///
+  /// It is a file path or an array of file paths, which are relative to the parameter file.
+  pub const EXTENDS: &'static str = "extends";
+
+  /// Fetch the layers of the API request parameters, which are merged in the following order:
+  /// 1. The defaults of the endpoint in the file of the setting `OPENAI_DEFAULTS`.
+  /// 2. The parameter files or the standard input, under each of which the base files of `extends` are merged.
+  /// 3. The inline items.
+  ///
+  /// The placeholders in each parameter file are rendered and then its file references are inlined before merging,
+  /// see [`ParameterTemplate`]. The inlined contents, the standard input and the inline items are left as they are.
+  /// The layers are empty if nothing is fetched.
+  /// It fails if the defaults, the inline items or the templates are invalid.
+  pub fn fetch_layers(context: &Context) -> Result<ParameterLayers> {
+    let mut layers = ParameterLayers::default();
+    let template = ParameterTemplate::new(context)?;
+    Self::fetch_defaults(context, &template, &mut layers)?;
+
+    let provided_files = &context.args.parameter_files;
+    let mut fetched = false;
+    for path in provided_files {
+      match Self::read_layers(context, &template, path, true, &mut vec![]) {
+        Ok(sources) => {
+          fetched |= !sources.is_empty();
+          Self::merge_sources(context, &mut layers, sources)?;
+        },
+        Err(err) if path == Path::new("-") => {
+          return Err(err.context("Failed to obtain the API request parameters from the standard input"));
+        },
+        Err(err) => warn!("Failed to obtain the API request parameters from {path:?}: {err:?}"),
+      }
+    }
+    if !fetched && provided_files.is_empty() {
+      // The standard input is used only if it is piped or redirected and not empty, in which case it must be valid.
+      let sources = Self::read_layers(context, &template, Path::new("-"), false, &mut vec![])
+        .map_err(|err| err.context("Failed to obtain the API request parameters from the standard input"))?;
+      fetched = !sources.is_empty();
+      Self::merge_sources(context, &mut layers, sources)?;
+    }
+    if !fetched {
+      for path in Self::DEFAULT_PATHS.map(PathBuf::from) {
+        match Self::read_layers(context, &template, &path, false, &mut vec![]) {
+          Ok(sources) => {
+            Self::merge_sources(context, &mut layers, sources)?;
+            break;
+          },
+          Err(err) => debug!("Failed to obtain the API request parameters from {path:?}: {err:?}"),
+        }
+      }
+    }
+
+    for item in &context.args.items {
+      layers.set(item, context)?;
+    }
+    if !context.args.items.is_empty() {
+      info!("Successfully merged {} inline items into the API request parameters", context.args.items.len());
+    }
+    Ok(layers)
+  }
+
+  /// Merge the sources in order after inlining their file references.
+  fn merge_sources(
+    context: &Context,
+    layers: &mut ParameterLayers,
+    sources: Vec<(String, serde_json::Value, PathBuf)>,
+  ) -> Result<()> {
+    for (source, mut value, base_dir) in sources {
+      ParameterTemplate::include(context, &mut value, &base_dir)
+        .map_err(|err| err.context(format!("Failed to inline the files in {source}")))?;
+      layers.merge(value, &source);
+    }
+    Ok(())
+  }
+
+  /// Merge the defaults of the matched endpoint in the file of the setting `OPENAI_DEFAULTS`,
+  /// which maps the path templates to the parameter objects, for example, `{"chat/completions": {"model": "gpt-4"}}`.
+  fn fetch_defaults(context: &Context, template: &ParameterTemplate, layers: &mut ParameterLayers) -> Result<()> {
+    let (path, source) = match context.setting("OPENAI_DEFAULTS") {
+      Ok(setting) => setting,
+      Err(err) => {
+        debug!("Skipped the defaults of the API request parameters: {err:?}");
+        return Ok(());
+      },
+    };
+    let Ok(endpoint) = loaders::Path::resolve(context) else {
+      return Ok(());
+    };
+    let defaults: serde_json::Value = context
+      .read(&path)
+      .and_then(|bytes| ParameterFormat::parse_file(Path::new(&path), &bytes))
+      .map_err(|err| err.context(format!("Failed to load the defaults {path:?} from {source}")))?;
+    let Some(value) = defaults
+      .as_object()
+      .into_iter()
+      .flatten()
+      .find(|(name, _)| loaders::Path::normalize(name) == endpoint.template())
+      .map(|(_, value)| value.clone())
+    else {
+      return Ok(());
+    };
+    let source = format!("the defaults of {:?} in the file {path:?}", endpoint.template());
+    let mut value = Parameter(value).post_fetch_ok(&source)?.value();
+    Self::render(context, template, &mut value, &source)?;
+    let base_dir = Path::new(&path).parent().map(Path::to_path_buf).unwrap_or_default();
+    Self::merge_sources(context, layers, vec![(source, value, base_dir)])
+  }
+
+  /// Read the parameter object from the file or the standard input of `-`,
+  /// and returns the sources, values and directories of its base files of `extends` recursively followed by itself.
+  ///
+  /// The sources are empty if the standard input is skipped, see [`Parameter::read_stdin`].
+  fn read_layers(
+    context: &Context,
+    template: &ParameterTemplate,
+    path: &Path,
+    provided: bool,
+    chain: &mut Vec<PathBuf>,
+  ) -> Result<Vec<(String, serde_json::Value, PathBuf)>> {
+    let (parameter, source, base_dir) = if path == Path::new("-") {
+      let source = "the standard input".to_string();
+      let Some(parameter) = Self::read_stdin(context, provided, &source)? else {
+        return Ok(vec![]);
+      };
+      (parameter, source, PathBuf::new())
+    } else {
+      let resolved = normalize(&context.resolve_path(path));
+      if chain.contains(&resolved) {
+        return Err(Error::msg(format!("The parameter file {path:?} extends itself in a cycle")));
+      }
+      chain.push(resolved);
+      let parameter = Parameter::from_file_slice(path, &context.read(path)?)?;
+      (parameter, format!("the file {path:?}"), path.parent().map(Path::to_path_buf).unwrap_or_default())
+    };
+    let mut value = parameter.post_fetch_ok(&source)?.value();
+    if path != Path::new("-") {
+      Self::render(context, template, &mut value, &source)?;
+    }
+
+    let bases = value.as_object_mut().and_then(|object| {
+      let bases = object.get(Self::EXTENDS).cloned();
+      object.retain(|name, _| name != Self::EXTENDS);
+      bases
+    });
+    let bases = match bases {
+      None => vec![],
+      Some(serde_json::Value::String(base)) => vec![base],
+      Some(serde_json::Value::Array(bases)) if bases.iter().all(serde_json::Value::is_string) => {
+        bases.into_iter().filter_map(|base| base.as_str().map(str::to_string)).collect()
+      },
+      Some(_) => return Err(Error::msg(format!(
+        "The field `{}` in {source} should be a file path or an array of file paths",
+        Self::EXTENDS,
+      ))),
+    };
+    let mut sources = vec![];
+    for base in bases {
+      let base = match base.starts_with('~') {
+        true => PathBuf::from(base),
+        false => normalize(&base_dir.join(base)),
+      };
+      sources.extend(
+        Self::read_layers(context, template, &base, false, chain)
+          .map_err(|err| err.context(format!("Failed to extend the parameter file {base:?} in {source}")))?,
+      );
+    }
+    if path != Path::new("-") {
+      chain.pop();
+    }
+    sources.push((source, value, base_dir));
+    Ok(sources)
+  }
+
+  /// Render the placeholders in the string values of the parameter file.
+  fn render(context: &Context, template: &ParameterTemplate, value: &mut serde_json::Value, source: &str) -> Result<()> {
+    template
+      .render(context, value)
+      .map_err(|err| err.context(format!("Failed to render the placeholders in {source}")))
+  }
+
+  /// Read the parameter object from the standard input.
+  ///
+  /// The object in JSON, which starts with `{`, is streamed by [`Parameter::from_reader`],
+  /// and the other formats are buffered and sniffed like the files without extensions.
+  /// It is skipped if it is a terminal, ignored or empty.
+  /// The failure of reading it is an error if it is provided, or a warning otherwise.
+  fn read_stdin(context: &Context, provided: bool, source: &str) -> Result<Option<Self>> {
+    let Some(reader) = context.stdin(provided) else {
+      debug!("Skipped {source} for being a terminal or ignored");
+      return Ok(None);
+    };
+    let mut reader = BufReader::new(reader);
+    let first = match peek(&mut reader) {
+      Ok(first) => first,
+      Err(err) if provided => return Err(Error::from(err).context(format!("Failed to read {source}"))),
+      Err(err) => {
+        warn!("Skipped {source} for failing to read it: {err:?}");
+        return Ok(None);
+      },
+    };
+    let Some(first) = first else {
+      let message = format!("Skipped {source} for being empty");
+      match provided {
+        true => warn!("{message}"),
+        false => debug!("{message}"),
+      }
+      return Ok(None);
+    };
+    if first == b'{' {
+      return Self::from_reader(reader).map(Some);
+    }
+    let mut bytes = vec![];
+    reader.read_to_end(&mut bytes)?;
+    Ok(Some(Self(ParameterFormat::sniff(&bytes, source)?)))
+  }
+
+  /// Validate the parameter object against the request schema of the operation in the catalog.
+  ///
+  /// It fails with all the violations. The operations without the request schema in JSON are skipped.
+  pub fn validate(&self, catalog: &Catalog, path: &loaders::Path, method: &Method) -> Result<()> {
+    let Some(request) = catalog
+      .endpoint(path.template())
+      .and_then(|endpoint| endpoint.operation(method.value_ref().as_str()))
+      .and_then(|operation| operation.request.as_ref())
+      .filter(|request| request.content_type == mime::APPLICATION_JSON.as_ref())
+    else {
+      debug!(
+        "Skipped the validation of the API request parameters for not having the request schema of {} {}",
+        method.value_ref(), path.template(),
+      );
+      return Ok(());
+    };
+    let violations = Schema(&request.schema).validate(self.value_ref());
+    if !violations.is_empty() {
+      return Err(Error::msg(format!(
+        "The API request parameters are invalid for {} {}:\n{}\n\
+        Provide the option `--no-validate` to skip the validation",
+        method.value_ref(),
+        path.template(),
+        violations.iter().map(|violation| format!("  {violation}")).collect::<Vec<_>>().join("\n"),
+      )));
+    }
+    info!(
+      "Successfully validated the API request parameters against the request schema of {} {}",
+      method.value_ref(), path.template(),
+    );
+    Ok(())
+  }
+
+  /// Warn if the field `model` is a deprecated model.
+  pub fn warn_deprecated_model(&self) {
+    let Some(model) = self.value_ref()
+      .get("model")
+      .and_then(serde_json::Value::as_str)
+      .and_then(deprecated_model)
+    else {
+      return;
+    };
+    warn!(
+      "The model {:?} is deprecated with the shutdown date {}, use the model {:?} instead",
+      model.name, model.shutdown, model.replacement,
+    );
+  }
+
   fn post_fetch_ok(self, source: &str) -> Result<Self> {
     info!(
       "Successfully fetched the API request parameters from {}: <JSON Object ({} bytes)>",
@@ -24,12 +380,52 @@ impl Parameter {
   }
 }
 
+/// Returns the first byte which is not a whitespace without consuming it, or `None` at the end of the reader.
+fn peek<R>(reader: &mut R) -> io::Result<Option<u8>>
+where
+  R: BufRead,
+{
+  loop {
+    let buffer = reader.fill_buf()?;
+    if buffer.is_empty() {
+      return Ok(None);
+    }
+    match buffer.iter().position(|byte| !byte.is_ascii_whitespace()) {
+      Some(index) => {
+        let byte = buffer[index];
+        reader.consume(index);
+        return Ok(Some(byte));
+      },
+      None => {
+        let length = buffer.len();
+        reader.consume(length);
+      },
+    }
+  }
+}
+
+/// Remove the components of `.` and `..` from the path lexically.
+fn normalize(path: &Path) -> PathBuf {
+  let mut normalized = PathBuf::new();
+  for component in path.components() {
+    match component {
+      Component::CurDir => {},
+      Component::ParentDir if matches!(normalized.components().last(), Some(Component::Normal(_))) => {
+        normalized.pop();
+      },
+      component => normalized.push(component),
+    }
+  }
+  normalized
+}
+
 impl FromFile for Parameter {
   fn from_file<P>(path: P) -> Result<Self>
   where
     P: AsRef<Path>,
   {
-    Ok(serde_json::from_reader(File::open(path)?)?)
+    let path = path.as_ref();
+    Self::from_file_slice(path, &fs::read(path)?)
   }
 }
 
@@ -42,26 +438,11 @@ impl FromStr for Parameter {
 }
 
 impl Loader<serde_json::Value> for Parameter {
-  fn fetch(entry: &Entry) -> Result<Self> {
-    for path in [
-        entry.parameter_file.as_ref(),
-        Some(&"openai.json".into()),
-        Some(&"openai-parameters.json".into()),
-        Some(&"openai_parameters.json".into()),
-        Some(&"openai-parameters".into()),
-        Some(&"openai_parameters".into()),
-        Some(&"openai.config.json".into()),
-      ]
-      .into_iter()
-      .flatten()
-    {
-      let source = &format!("the file {path:?}");
-      match Parameter::from_file(path) {
-        Ok(parameter) => return parameter.post_fetch_ok(source),
-        Err(err) => debug!("Failed to obtain the API request parameters from {source}: {err:?}"),
-      }
-    }
-    Err(Error::msg("Failed to fetch the API request parameters"))
+  fn fetch(context: &Context) -> Result<Self> {
+    Self::fetch_layers(context)?
+      .value()
+      .map(Parameter)
+      .ok_or(Error::msg("Failed to fetch the API request parameters"))
   }
   fn value(self) -> serde_json::Value {
     self.0
@@ -1,47 +1,144 @@
-use crate::{Entry, Error, Result, traits::*};
+use crate::{legacy_replacement, replacement, Catalog, Context, Error, Result, Router, Template, traits::*};
 use std::str::FromStr;
-use regex::Regex;
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 
 /// The API request path.
-pub struct Path(String);
+pub struct Path {
+  path: String,
+  template: String,
+  params: Vec<(String, String)>,
+}
+
+impl Path {
+  /// Create a new path object from the path, in which the path parameters in curly braces are substituted.
+  ///
+  /// The origin and the version prefix `v1` are removed, for example,
+  /// `https://api.openai.com/v1/files/{file_id}/content` with `file_id=file-abc` becomes `files/file-abc/content`.
+  pub fn with_params(path: &str, params: &[(String, String)]) -> Result<Self> {
+    Self::with_router(Router::embedded(), path, params)
+  }
+
+  /// Create a new path object like [`Path::with_params`], which is matched against the endpoints in the catalog.
+  pub fn with_catalog(catalog: &Catalog, path: &str, params: &[(String, String)]) -> Result<Self> {
+    Self::with_router(&Router::new(catalog), path, params)
+  }
+
+  /// Create a new path object like [`Path::with_params`], which is matched by the router.
+  pub fn with_router(router: &Router, path: &str, params: &[(String, String)]) -> Result<Self> {
+    let path = Template::from_str(Self::normalize(path))?.render(params)?;
+    let route = router
+      .route(&path)
+      .ok_or_else(|| {
+        let suggestions = router.catalog().suggest(&path);
+        let message = format!("Invalid format of OpenAI API request path: {path:?}");
+        Error::msg(match suggestions.as_slice() {
+          [] => message,
+          [suggestion] => format!("{message}\nDid you mean {suggestion}?"),
+          suggestions => format!(
+            "{message}\nDid you mean one of these? {}",
+            suggestions.iter().map(ToString::to_string).collect::<Vec<_>>().join(", "),
+          ),
+        })
+      })?;
+    Ok(Self {
+      path,
+      template: route.endpoint.path.clone(),
+      params: route.params,
+    })
+  }
+
+  /// Returns the path template of the matched endpoint, for example, `files/{file_id}/content`.
+  pub fn template(&self) -> &str {
+    &self.template
+  }
+
+  /// Returns the captured path parameters.
+  pub fn params(&self) -> &[(String, String)] {
+    &self.params
+  }
+
+  /// Resolve the path object from the resolution context without logging.
+  pub(crate) fn resolve(context: &Context) -> Result<Self> {
+    Self::with_router(&context.router(), &context.args.path, &context.args.path_params)
+  }
+
+  /// Warn about the path parameters which are not in the path given by the program arguments.
+  fn warn_ignored_params(context: &Context) -> Result<()> {
+    let path = Self::normalize(&context.args.path);
+    let template = Template::from_str(path)?;
+    for (name, _) in &context.args.path_params {
+      if !template.params().contains(&name.as_str()) {
+        warn!("Ignored the path parameter `{name}` for not being in the API request path {path:?}");
+      }
+    }
+    Ok(())
+  }
+
+  /// Warn if the operations on the matched endpoint in the catalog are deprecated or legacy.
+  fn warn_deprecation(&self, catalog: &Catalog) {
+    let Some(endpoint) = catalog.endpoint(self.template()) else {
+      return;
+    };
+    let (state, replacement) = if endpoint.is_deprecated() {
+      ("deprecated", replacement(self.template()))
+    } else if endpoint.is_legacy() {
+      ("legacy", legacy_replacement(self.template()))
+    } else {
+      return;
+    };
+    match replacement {
+      Some(replacement) => warn!(
+        "The API request path {:?} is {state}, use the endpoint `{replacement}` instead",
+        self.template(),
+      ),
+      None => warn!("The API request path {:?} is {state}", self.template()),
+    }
+  }
+
+  /// Remove the origin, the version prefix `v1` and the surrounding slashes from the path.
+  pub(crate) fn normalize(path: &str) -> &str {
+    let path = match path.split_once("://") {
+      Some((_, url)) => url.split_once('/').map_or("", |(_, path)| path),
+      None => path,
+    };
+    let path = path.trim_matches('/');
+    path.strip_prefix("v1/").unwrap_or(path)
+  }
+}
 
 impl FromStr for Path {
   type Err = Error;
 
   fn from_str(path: &str) -> Result<Self> {
-    Ok(Self(
-      Regex::new(include_str!("../../../assets/openai-openapi-paths-regex"))?
-        .find(path)
-        .ok_or(Error::msg("Invalid format of OpenAI API key"))?
-        .as_str()
-        .to_string()
-    ))
+    Self::with_params(path, &[])
   }
 }
 
 impl Loader<String> for Path {
-  fn fetch(entry: &Entry) -> Result<Self> {
+  fn fetch(context: &Context) -> Result<Self> {
     let source = "the program arguments";
-    match Path::from_str(&entry.path) {
+    match Path::resolve(context) {
       Ok(path) => {
         info!(
-          "Successfully fetched the API request path from {source}: {:?}",
+          "Successfully fetched the API request path from {source}: {:?} (matched {:?})",
           path.value_ref(),
+          path.template(),
         );
+        Self::warn_ignored_params(context)?;
+        path.warn_deprecation(context.catalog());
         Ok(path)
       },
       Err(err) => {
         debug!("Failed to obtain the API request path from {source}: {err:?}");
-        Err(Error::msg("Failed to fetch the API request path"))
+        Err(err.context("Failed to fetch the API request path"))
       },
     }
   }
   fn value(self) -> String {
-    self.0
+    self.path
   }
   fn value_ref(&self) -> &String {
-    &self.0
+    &self.path
   }
 }
 
@@ -0,0 +1,83 @@
+use crate::{Catalog, Context, Result, Router, Template, traits::*};
+use std::{fs, path::Path, sync::{Arc, OnceLock}};
+use tracing::{debug, info};
+
+/// The catalog of the OpenAPI specification, which drives the path, method and schema checks.
+#[derive(Clone)]
+pub struct Spec {
+  catalog: Arc<Catalog>,
+  templates: Arc<[Template]>,
+}
+
+impl Spec {
+  /// Create a spec object from bytes of the OpenAPI document in YAML or JSON.
+  pub fn from_slice(slice: &[u8]) -> Result<Self> {
+    let document: serde_json::Value = serde_yaml::from_slice(slice)?;
+    Ok(Self::from(Catalog::from_openapi(&document)?))
+  }
+
+  /// Returns the spec object of the embedded catalog.
+  pub fn embedded() -> Self {
+    static SPEC: OnceLock<Spec> = OnceLock::new();
+    SPEC.get_or_init(|| Self::from(Catalog::embedded().clone())).clone()
+  }
+
+  /// Returns the router over the catalog, whose path templates are parsed once on loading.
+  pub fn router(&self) -> Router<'_> {
+    Router::with_templates(&self.catalog, &self.templates)
+  }
+
+  fn load(context: &Context, path: &Path, source: &str) -> Result<Self> {
+    context
+      .read(path)
+      .and_then(|bytes| Spec::from_slice(&bytes))
+      .map_err(|err| err.context(format!("Failed to load the OpenAPI specification {path:?} from {source}")))?
+      .post_fetch_ok(&format!("the file {path:?} provided by {source}"))
+  }
+
+  fn post_fetch_ok(self, source: &str) -> Result<Self> {
+    info!(
+      "Successfully fetched the OpenAPI specification from {source}: <Catalog ({} endpoints)>",
+      self.value_ref().endpoints.len(),
+    );
+    Ok(self)
+  }
+}
+
+impl FromFile for Spec {
+  fn from_file<P>(path: P) -> Result<Self>
+  where
+    P: AsRef<Path>,
+  {
+    Self::from_slice(&fs::read(path)?)
+  }
+}
+
+impl Loader<Arc<Catalog>> for Spec {
+  fn fetch(context: &Context) -> Result<Self> {
+    if let Some(provided_file) = context.args.spec_file.as_ref() {
+      return Self::load(context, provided_file, "the program arguments");
+    }
+
+    match context.setting("OPENAI_SPEC") {
+      Ok((path, source)) => return Self::load(context, Path::new(&path), &source),
+      Err(err) => debug!("Failed to obtain the OpenAPI specification from the setting `OPENAI_SPEC`: {err:?}"),
+    }
+    Self::embedded().post_fetch_ok("the embedded catalog")
+  }
+  fn value(self) -> Arc<Catalog> {
+    self.catalog
+  }
+  fn value_ref(&self) -> &Arc<Catalog> {
+    &self.catalog
+  }
+}
+
+impl From<Catalog> for Spec {
+  fn from(catalog: Catalog) -> Self {
+    Self {
+      templates: Router::templates(&catalog).into(),
+      catalog: Arc::new(catalog),
+    }
+  }
+}
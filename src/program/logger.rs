@@ -0,0 +1,84 @@
+use crate::{redaction::Redactor, Result};
+use clap::ValueEnum;
+use std::{fs::OpenOptions, io::stderr, path::PathBuf};
+use tracing::Level;
+use tracing_subscriber::{
+  fmt::{self, writer::BoxMakeWriter},
+  layer::SubscriberExt,
+  util::SubscriberInitExt,
+  EnvFilter,
+  Layer,
+  Registry,
+};
+
+/// The format of logs.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, ValueEnum)]
+pub enum LogFormat {
+  /// Human-readable text.
+  #[default]
+  Text,
+  /// One JSON object per line.
+  Json,
+}
+
+/// The logger of the program.
+pub struct Logger {
+  /// The file path where the logs are appended to. The logs are written to the standard error if it is `None`.
+  pub file: Option<PathBuf>,
+
+  /// The filter directives in the same syntax as `RUST_LOG`, which override the verbosity if provided.
+  pub filter: Option<String>,
+
+  /// The format of logs.
+  pub format: LogFormat,
+
+  /// The redactor of logs.
+  pub redactor: Redactor,
+
+  /// The verbosity, where `0` logs warnings and errors, negative values log less and positive values log more.
+  pub verbosity: i8,
+}
+
+impl Logger {
+  /// Returns the filter directives of the logger.
+  pub fn directives(&self) -> String {
+    if let Some(filter) = self.filter.as_ref() {
+      return filter.clone();
+    }
+    match self.verbosity {
+      i8::MIN..=-2 => "off".to_string(),
+      -1 => Level::ERROR.to_string(),
+      0 => Level::WARN.to_string(),
+      1 => Level::DEBUG.to_string(),
+      2.. => format!("{},{}={}", Level::DEBUG, env!("CARGO_CRATE_NAME"), Level::TRACE),
+    }
+  }
+
+  /// Install the logger as the global default subscriber.
+  pub fn init(self) -> Result<()> {
+    let filter = EnvFilter::try_new(self.directives())?;
+    let detailed = self.verbosity > 0 || self.filter.is_some();
+    let (writer, to_file) = match self.file.as_ref() {
+      Some(path) => {
+        // the final error is reported to the standard error by the program, not by the logger
+        (BoxMakeWriter::new(OpenOptions::new().append(true).create(true).open(path)?), true)
+      },
+      None => (BoxMakeWriter::new(stderr), false),
+    };
+    let layer = fmt::layer()
+      .with_ansi(!to_file)
+      .with_file(detailed)
+      .with_line_number(detailed)
+      .with_target(false)
+      .with_writer(self.redactor.make_writer(writer));
+    let layer: Box<dyn Layer<Registry> + Send + Sync> = match (self.format, to_file) {
+      (LogFormat::Text, false) => layer.without_time().boxed(),
+      (LogFormat::Text, true) => layer.boxed(),
+      (LogFormat::Json, _) => layer.json().boxed(),
+    };
+    tracing_subscriber::registry()
+      .with(layer.with_filter(filter))
+      .try_init()?;
+    Ok(())
+  }
+}
@@ -1,11 +1,35 @@
+/// Subcommand module.
+pub mod command;
+pub use command::*;
+
+/// Resolution context module.
+pub mod context;
+pub use context::*;
+
 /// Program entry module.
 pub mod entry;
 pub use entry::*;
 
+/// Inline parameter item module.
+pub mod item;
+pub use item::*;
+
+/// Parameter layer module.
+pub mod layers;
+pub use layers::*;
+
 /// Argument loader module.
 pub mod loaders;
 pub use loaders::*;
 
+/// Logger module.
+pub mod logger;
+pub use logger::*;
+
+/// Parameter template module.
+pub mod template;
+pub use template::*;
+
 /// Traits module.
 pub mod traits;
 pub use traits::*;
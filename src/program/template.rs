@@ -0,0 +1,199 @@
+use crate::{escape_pointer, Context, Error, ParameterFormat, Result};
+use regex::{Captures, Regex};
+use serde_json::{Map, Value};
+use std::path::Path;
+use tracing::info;
+
+/// The templating pass over the API request parameters.
+///
+/// The string values of the parameter files are rendered with the placeholders:
+/// - `{{name}}` is filled with the variable from the option `--var` or the variable file, and `$${{` is the escaped `{{`.
+/// - `${NAME}` is expanded to the environment variable, and `$${` is the escaped `${`.
+///
+/// The files are inlined after rendering, whose contents are not rendered:
+/// - `{"$include": "path"}` is replaced with the contents of the file,
+///   which are parsed if the file extension is of a parameter file format, or else inlined as a string.
+/// - `"@path"` is replaced with the contents of the file as a string, and `"@@"` is the escaped `"@"`.
+pub struct ParameterTemplate {
+  vars: Map<String, Value>,
+}
+
+impl ParameterTemplate {
+  /// The key of the object which is replaced with the contents of the file.
+  pub const INCLUDE: &'static str = "$include";
+
+  /// The maximum depth of the nested file inclusions.
+  pub const MAX_INCLUDE_DEPTH: usize = 16;
+
+  /// Create the template from the variable file and the variables of the resolution context,
+  /// in which the latter ones take precedence.
+  pub fn new(context: &Context) -> Result<Self> {
+    let mut vars = Map::new();
+    if let Some(path) = context.args.vars_file.as_ref() {
+      let file = context
+        .read(path)
+        .and_then(|bytes| ParameterFormat::parse_file(path, &bytes))
+        .map_err(|err| err.context(format!("Failed to load the variable file {path:?}")))?;
+      match file {
+        Value::Object(file) => vars.extend(file),
+        _ => return Err(Error::msg(format!("The variable file {path:?} should be an object"))),
+      }
+      info!("Successfully fetched the template variables from the file {path:?}");
+    }
+    for (name, value) in &context.args.vars {
+      vars.insert(name.clone(), Value::String(value.clone()));
+    }
+    Ok(Self { vars })
+  }
+
+  /// Replace the file references in the value with the contents of the files,
+  /// which are relative to the directory `base_dir`.
+  pub fn include(context: &Context, value: &mut Value, base_dir: &Path) -> Result<()> {
+    Self::include_at(context, value, base_dir, &mut String::new(), 0)
+  }
+
+  /// Render the placeholders of the variables and the environment variables in the string values.
+  ///
+  /// It fails with the JSON pointer if a variable or an environment variable is undefined.
+  pub fn render(&self, context: &Context, value: &mut Value) -> Result<()> {
+    let pattern = Regex::new(r"\$\$\{\{|\$\$\{|\$\{([A-Za-z_][A-Za-z0-9_]*)\}|\{\{\s*([A-Za-z_][A-Za-z0-9_.-]*)\s*\}\}")?;
+    self.render_at(context, value, &pattern, &mut String::new())
+  }
+
+  fn include_at(
+    context: &Context,
+    value: &mut Value,
+    base_dir: &Path,
+    pointer: &mut String,
+    depth: usize,
+  ) -> Result<()> {
+    let path = match value {
+      Value::Object(object) if object.len() == 1 && object.contains_key(Self::INCLUDE) => {
+        match &object[Self::INCLUDE] {
+          Value::String(path) => path.clone(),
+          _ => return Err(Error::msg(format!(
+            "The field `{}` at `{pointer}` should be a file path",
+            Self::INCLUDE,
+          ))),
+        }
+      },
+      Value::String(text) if text.starts_with("@@") => {
+        text.remove(0);
+        return Ok(());
+      },
+      Value::String(text) if text.starts_with('@') => {
+        let path = base_dir.join(&text[1..]);
+        *text = context
+          .read_to_string(&path)
+          .map_err(|err| err.context(format!("Failed to inline the file {path:?} at `{pointer}`")))?;
+        return Ok(());
+      },
+      Value::Object(object) => {
+        for (key, value) in object {
+          let length = pointer.len();
+          pointer.push_str(&format!("/{}", escape_pointer(key)));
+          Self::include_at(context, value, base_dir, pointer, depth)?;
+          pointer.truncate(length);
+        }
+        return Ok(());
+      },
+      Value::Array(array) => {
+        for (index, value) in array.iter_mut().enumerate() {
+          let length = pointer.len();
+          pointer.push_str(&format!("/{index}"));
+          Self::include_at(context, value, base_dir, pointer, depth)?;
+          pointer.truncate(length);
+        }
+        return Ok(());
+      },
+      _ => return Ok(()),
+    };
+
+    if depth >= Self::MAX_INCLUDE_DEPTH {
+      return Err(Error::msg(format!(
+        "The file {path:?} at `{pointer}` is included more than {} levels deep, which may be in a cycle",
+        Self::MAX_INCLUDE_DEPTH,
+      )));
+    }
+    let path = base_dir.join(path);
+    let bytes = context
+      .read(&path)
+      .map_err(|err| err.context(format!("Failed to include the file {path:?} at `{pointer}`")))?;
+    *value = match ParameterFormat::from_path(&path) {
+      Some(format) => {
+        let mut included = format
+          .parse(&bytes)
+          .map_err(|err| err.context(format!("Failed to include the file {path:?} at `{pointer}`")))?;
+        let base_dir = path.parent().unwrap_or(base_dir);
+        Self::include_at(context, &mut included, base_dir, pointer, depth + 1)?;
+        included
+      },
+      None => Value::String(String::from_utf8(bytes)?),
+    };
+    Ok(())
+  }
+
+  fn render_at(&self, context: &Context, value: &mut Value, pattern: &Regex, pointer: &mut String) -> Result<()> {
+    match value {
+      Value::String(text) => {
+        let typed = pattern
+          .captures(text)
+          .filter(|captures| captures[0].len() == text.len())
+          .and_then(|captures| self.vars.get(captures.get(2)?.as_str()))
+          .filter(|var| !var.is_string());
+        if let Some(var) = typed {
+          *value = var.clone();
+          return Ok(());
+        }
+        let mut error = None;
+        let rendered = pattern.replace_all(text, |captures: &Captures| {
+          let (name, found) = match (captures.get(1), captures.get(2)) {
+            (Some(name), _) => {
+              let found = context.var(name.as_str()).ok().cloned();
+              (format!("environment variable `{}`", name.as_str()), found)
+            },
+            (_, Some(name)) => {
+              let found = self.vars.get(name.as_str()).map(|var| match var {
+                Value::String(var) => var.clone(),
+                var => var.to_string(),
+              });
+              (format!("variable `{}`", name.as_str()), found)
+            },
+            _ if &captures[0] == "$${{" => return "{{".to_string(),
+            _ => return "${".to_string(),
+          };
+          found.unwrap_or_else(|| {
+            error.get_or_insert(name);
+            String::new()
+          })
+        })
+        .into_owned();
+        if let Some(name) = error {
+          let pointer = if pointer.is_empty() { "/" } else { pointer };
+          return Err(Error::msg(format!(
+            "The {name} is undefined at `{pointer}` of the API request parameters",
+          )));
+        }
+        *text = rendered;
+      },
+      Value::Object(object) => {
+        for (key, value) in object {
+          let length = pointer.len();
+          pointer.push_str(&format!("/{}", escape_pointer(key)));
+          self.render_at(context, value, pattern, pointer)?;
+          pointer.truncate(length);
+        }
+      },
+      Value::Array(array) => {
+        for (index, value) in array.iter_mut().enumerate() {
+          let length = pointer.len();
+          pointer.push_str(&format!("/{index}"));
+          self.render_at(context, value, pattern, pointer)?;
+          pointer.truncate(length);
+        }
+      },
+      _ => {},
+    }
+    Ok(())
+  }
+}
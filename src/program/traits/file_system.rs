@@ -0,0 +1,34 @@
+use std::{io::{self, Read, Write}, path::Path};
+
+/// A trait to access the file system.
+pub trait FileSystem
+where
+  Self: Send + Sync,
+{
+  /// Read the entire contents of a file into bytes.
+  fn read(&self, path: &Path) -> io::Result<Vec<u8>>;
+
+  /// Check if a file or a directory exists.
+  fn exists(&self, path: &Path) -> bool;
+
+  /// Open a file for appending, or create it if it does not exist, and returns the writer of it.
+  fn append(&self, path: &Path) -> io::Result<Box<dyn Write>>;
+
+  /// Create a file, or truncate it if it exists, and returns the writer of it.
+  fn create(&self, path: &Path) -> io::Result<Box<dyn Write>>;
+
+  /// Create a directory and all of its missing parent directories.
+  fn create_dir_all(&self, path: &Path) -> io::Result<()>;
+
+  /// Remove a file.
+  fn remove_file(&self, path: &Path) -> io::Result<()>;
+
+  /// Rename a file, which replaces the destination file if it exists.
+  fn rename(&self, from: &Path, to: &Path) -> io::Result<()>;
+
+  /// Returns the reader of the standard input.
+  fn stdin(&self) -> Box<dyn Read>;
+
+  /// Check if the standard input is a terminal; otherwise, it is piped or redirected.
+  fn stdin_is_terminal(&self) -> bool;
+}
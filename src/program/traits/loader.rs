@@ -1,12 +1,12 @@
-use crate::{Entry, Result};
+use crate::{Context, Result};
 
-/// A trait to load an object from the program entry.
+/// A trait to load an object from the resolution context.
 pub trait Loader<T>
 where
   Self: Sized
 {
-  /// Fetch an object from the program entry.
-  fn fetch(entry: &Entry) -> Result<Self>;
+  /// Fetch an object from the resolution context.
+  fn fetch(context: &Context) -> Result<Self>;
   /// Move the sealed object out.
   fn value(self) -> T;
   /// Returns the refernece of the sealed object.
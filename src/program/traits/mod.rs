@@ -1,3 +1,7 @@
+/// File system trait.
+pub mod file_system;
+pub use file_system::*;
+
 /// File loader trait.
 pub mod from_file;
 pub use from_file::*;
@@ -1,10 +1,27 @@
 use crate::{loaders::*, service::*, traits::*, Result};
-use http::header::AUTHORIZATION;
-use reqwest::{
-  Client as ReqwestClient,
-  Response,
+use http::{header::AUTHORIZATION, uri::Scheme, Uri};
+use hyper::{
+  client::{connect::dns::Name, HttpConnector},
+  service::Service,
+  Body, Client as HyperClient,
 };
-use tracing::info;
+use hyper_tls::MaybeHttpsStream;
+use reqwest::{Client as ReqwestClient, Request, Response};
+use serde_json::{json, Map, Value};
+use sha2::{Digest, Sha256};
+use std::{
+  error::Error as StdError,
+  future::Future,
+  io,
+  net::SocketAddr,
+  pin::Pin,
+  task::{Context as TaskContext, Poll},
+  time::Instant,
+  vec,
+};
+use tokio::net::TcpStream;
+use tokio_native_tls::{native_tls, TlsConnector};
+use tracing::{enabled, info, trace, Level};
 
 /// The client to send the OpenAI API requests.
 pub struct OpenAIClient {
@@ -18,22 +35,23 @@ impl OpenAIClient {
     Self { key, organization }
   }
 
-  /// Send a request to the OpenAI API and receive the response.
-  pub async fn send(&self, request: OpenAIRequest) -> Result<Response> {
+  /// Send a request to the OpenAI API and resolve the response.
+  pub async fn send(&self, request: OpenAIRequest) -> Result<OpenAIResponse> {
     let method = request.method.value();
     let url = request.url;
     let authorization = format!("Bearer {}", self.key.value_ref());
     let organization = self.organization
       .as_ref()
       .map(Loader::value_ref);
-    let body = request.parameter
+    let parameter = request.parameter;
+    let body = parameter
       .as_ref()
       .map(Loader::value_ref);
 
     let client = ReqwestClient::new();
 
     info!("Sending request to {:?}", url.to_string());
-    let mut request = client.request(method, url);
+    let mut request = client.request(method.clone(), url);
     request = request.header(AUTHORIZATION, authorization);
     if let Some(organization) = organization {
       request = request.header("OpenAI-Organization", organization);
@@ -41,8 +59,134 @@ impl OpenAIClient {
     if let Some(body) = body {
       request = request.json(body);
     }
-    let response = request.send().await?;
+    let request = request.build()?;
+    trace!("> {} {} {:?}", request.method(), request.url(), request.version());
+    for (name, value) in request.headers() {
+      trace!("> {}: {}", name, redact_header(name, value));
+    }
+    if let Some(body) = request.body().and_then(|body| body.as_bytes()) {
+      trace!("> <Body ({} bytes)>", body.len());
+      trace!("> {}", redact_text(&String::from_utf8_lossy(body)));
+    }
+
+    let request_hash = request
+      .body()
+      .and_then(|body| body.as_bytes())
+      .map(|body| format!("{:x}", Sha256::digest(body)));
+    let mut credentials = Map::new();
+    credentials.insert("key".to_string(), credential(self.key.value_ref(), self.key.source()));
+    if let Some(organization) = self.organization.as_ref() {
+      credentials.insert("organization".to_string(), credential(organization.value_ref(), organization.source()));
+    }
+
+    let url = request.url().clone();
+    let start = Instant::now();
+    let response = match enabled!(Level::TRACE) {
+      true => execute_timed(request).await?,
+      false => client.execute(request).await?,
+    };
     info!("Received the API response: {}", response.status());
-    Ok(response)
+    trace!("< {:?} {}", response.version(), response.status());
+    for (name, value) in response.headers() {
+      trace!("< {}: {}", name, redact_header(name, value));
+    }
+    trace!("Timing: first byte after {:?}", start.elapsed());
+    Ok(OpenAIResponse {
+      credentials,
+      method: Some(method),
+      parameter: parameter.map(Loader::value),
+      request_hash,
+      ..OpenAIResponse::resolve_with_url(response, url, start).await?
+    })
+  }
+}
+
+/// Send the request over a direct connection of [`TimedConnector`] in the wire tracing mode,
+/// which traces the timings of DNS lookup, connection and TLS handshake.
+async fn execute_timed(request: Request) -> Result<Response> {
+  let mut http = HttpConnector::new_with_resolver(TimedResolver);
+  http.enforce_http(false);
+  let tls = TlsConnector::from(native_tls::TlsConnector::new()?);
+  let client = HyperClient::builder().build::<_, Body>(TimedConnector { http, tls });
+
+  let mut builder = http::Request::builder()
+    .method(request.method().clone())
+    .uri(request.url().as_str());
+  if let Some(headers) = builder.headers_mut() {
+    *headers = request.headers().clone();
+  }
+  let body = request
+    .body()
+    .and_then(|body| body.as_bytes())
+    .map_or_else(Body::empty, |body| Body::from(body.to_vec()));
+  Ok(Response::from(client.request(builder.body(body)?).await?))
+}
+
+/// Returns the masked value and the source of the credential.
+fn credential(value: &str, source: Option<&str>) -> Value {
+  json!({ "masked": mask(value), "source": source })
+}
+
+/// The boxed future of the connectors.
+type Connecting<T, E> = Pin<Box<dyn Future<Output = Result<T, E>> + Send>>;
+
+/// The DNS resolver which traces the time spent on lookups.
+#[derive(Clone)]
+struct TimedResolver;
+
+impl Service<Name> for TimedResolver {
+  type Response = vec::IntoIter<SocketAddr>;
+  type Error = io::Error;
+  type Future = Connecting<Self::Response, Self::Error>;
+
+  fn poll_ready(&mut self, _: &mut TaskContext<'_>) -> Poll<Result<(), Self::Error>> {
+    Poll::Ready(Ok(()))
+  }
+  fn call(&mut self, name: Name) -> Self::Future {
+    Box::pin(async move {
+      let start = Instant::now();
+      let addrs: Vec<_> = tokio::net::lookup_host((name.as_str(), 0))
+        .await?
+        .collect();
+      trace!(
+        "Timing: resolved {:?} to {:?} in {:?}",
+        name.as_str(), addrs, start.elapsed(),
+      );
+      Ok(addrs.into_iter())
+    })
+  }
+}
+
+/// The connector which traces the time spent on the TCP connection and the TLS handshake separately.
+#[derive(Clone)]
+struct TimedConnector {
+  http: HttpConnector<TimedResolver>,
+  tls: TlsConnector,
+}
+
+impl Service<Uri> for TimedConnector {
+  type Response = MaybeHttpsStream<TcpStream>;
+  type Error = Box<dyn StdError + Send + Sync>;
+  type Future = Connecting<Self::Response, Self::Error>;
+
+  fn poll_ready(&mut self, cx: &mut TaskContext<'_>) -> Poll<Result<(), Self::Error>> {
+    self.http.poll_ready(cx).map_err(Into::into)
+  }
+  fn call(&mut self, uri: Uri) -> Self::Future {
+    let Self { mut http, tls } = self.clone();
+    Box::pin(async move {
+      let host = uri.host().unwrap_or_default().trim_matches(|char| char == '[' || char == ']').to_string();
+      let https = uri.scheme() == Some(&Scheme::HTTPS);
+      let start = Instant::now();
+      let stream = http.call(uri).await?;
+      trace!("Timing: connected to {host:?} in {:?}", start.elapsed());
+      if !https {
+        return Ok(MaybeHttpsStream::Http(stream));
+      }
+      let start = Instant::now();
+      let stream = tls.connect(&host, stream).await?;
+      trace!("Timing: TLS handshake with {host:?} in {:?}", start.elapsed());
+      Ok(MaybeHttpsStream::Https(stream))
+    })
   }
 }
@@ -5,3 +5,15 @@ pub use client::*;
 /// The OpenAI request module.
 pub mod request;
 pub use request::*;
+
+/// The secret redaction module.
+pub mod redaction;
+pub use redaction::*;
+
+/// The JSON selection module.
+pub mod selection;
+pub use selection::*;
+
+/// The OpenAI response module.
+pub mod response;
+pub use response::*;
@@ -0,0 +1,400 @@
+use crate::{loaders::*, openapi::*, service::*, Error, Result};
+use eventsource_stream::Eventsource;
+use futures_util::{stream, Stream, StreamExt};
+use http::{header::CONTENT_TYPE, HeaderMap, Method, StatusCode};
+use mime::Mime;
+use reqwest::Response;
+use std::{io::Write, pin::Pin, time::{Duration, Instant}};
+use tracing::{info, trace, warn};
+use url::Url;
+
+/// The stream of data in the server-sent events.
+///
+/// It ends before the data `[DONE]`.
+pub type EventStream = Pin<Box<dyn Stream<Item = Result<String>> + Send>>;
+
+/// The mode of checking the API response against the response schema.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, clap::ValueEnum)]
+pub enum ResponseCheck {
+  /// The violations are logged as warnings.
+  #[default]
+  Warn,
+
+  /// The violations are reported as errors.
+  Strict,
+}
+
+/// The format of the exported response body.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, clap::ValueEnum)]
+pub enum OutputFormat {
+  /// The indented JSON, in which the data in the server-sent events are written in one line each.
+  #[default]
+  Pretty,
+
+  /// The JSON in one line.
+  Compact,
+
+  /// The newline-delimited JSON, in which the items of the list response in the field `data` are written one per line.
+  Ndjson,
+
+  /// The YAML documents.
+  Yaml,
+
+  /// The JSON body as received, byte for byte.
+  ///
+  /// The server-sent events are not kept byte for byte, in which only the data of each event are written as received
+  /// one per line, without the framing of the events such as `data:` and the blank lines.
+  Raw,
+}
+
+impl OutputFormat {
+  /// Format the JSON body, which is received as the raw bytes.
+  pub fn format_json(self, value: &serde_json::Value, raw: &[u8]) -> Result<Vec<u8>> {
+    Ok(match self {
+      Self::Pretty => serde_json::to_string_pretty(value)?,
+      Self::Compact => format!("{}\n", serde_json::to_string(value)?),
+      Self::Ndjson => match value.get("data").and_then(serde_json::Value::as_array) {
+        Some(items) => items
+          .iter()
+          .map(|item| Ok(format!("{}\n", serde_json::to_string(item)?)))
+          .collect::<Result<String>>()?,
+        None => format!("{}\n", serde_json::to_string(value)?),
+      },
+      Self::Yaml => serde_yaml::to_string(value)?,
+      Self::Raw => return Ok(raw.to_vec()),
+    }.into_bytes())
+  }
+
+  /// Format the data in the server-sent events, which is written as received if it is not JSON.
+  ///
+  /// The data are written in one line each, even in the pretty format.
+  pub fn format_data(self, data: &str) -> Result<String> {
+    let value = match self {
+      Self::Raw => None,
+      _ => serde_json::from_str::<serde_json::Value>(data).ok(),
+    };
+    Ok(match (self, value) {
+      (Self::Pretty | Self::Compact | Self::Ndjson, Some(value)) => format!("{}\n", serde_json::to_string(&value)?),
+      (Self::Yaml, Some(value)) => format!("---\n{}", serde_yaml::to_string(&value)?),
+      _ => format!("{data}\n"),
+    })
+  }
+}
+
+/// The options of exporting the response body.
+#[derive(Clone, Debug, Default)]
+pub struct ExportOptions {
+  /// The format of the exported response body.
+  pub format: OutputFormat,
+
+  /// Whether the selected strings are written without quotes.
+  pub raw_output: bool,
+
+  /// The selection query, whose selected values are written one by one instead of the whole body.
+  pub select: Option<Selector>,
+}
+
+impl ExportOptions {
+  /// Format the JSON body, which is received as the raw bytes.
+  ///
+  /// The selected values are written if the selection query is provided.
+  pub fn format_json(&self, value: &serde_json::Value, raw: &[u8]) -> Result<Vec<u8>> {
+    match self.select.as_ref() {
+      Some(selector) => Ok(self.format_selection(selector, value, false)?.into_bytes()),
+      None => self.format.format_json(value, raw),
+    }
+  }
+
+  /// Format the data in the server-sent events.
+  ///
+  /// The selected values are written if the selection query is provided, in which case the data should be JSON.
+  pub fn format_data(&self, data: &str) -> Result<String> {
+    match self.select.as_ref() {
+      Some(selector) => {
+        let value = serde_json::from_str(data)
+          .map_err(|err| Error::from(err).context("The API response data is not in JSON format"))?;
+        self.format_selection(selector, &value, true)
+      },
+      None => self.format.format_data(data),
+    }
+  }
+
+  /// Format the selected values, which are written in one line each for the data in the server-sent events.
+  /// Returns the file extension of the exported body, which is `txt` for the raw selected strings,
+  /// `yaml` for YAML and `jsonl` for NDJSON, or `None` if it follows the content type of the response.
+  pub fn extension(&self) -> Option<&'static str> {
+    match self.format {
+      _ if self.select.is_some() && self.raw_output => Some("txt"),
+      OutputFormat::Yaml => Some("yaml"),
+      OutputFormat::Ndjson => Some("jsonl"),
+      _ => None,
+    }
+  }
+
+  fn format_selection(&self, selector: &Selector, value: &serde_json::Value, stream: bool) -> Result<String> {
+    selector
+      .select(value)?
+      .into_iter()
+      .map(|value| Ok(match (value, self.format) {
+        (serde_json::Value::String(text), _) if self.raw_output => format!("{text}\n"),
+        (value, OutputFormat::Pretty) if !stream => format!("{}\n", serde_json::to_string_pretty(value)?),
+        (value, OutputFormat::Yaml) => format!("---\n{}", serde_yaml::to_string(value)?),
+        (value, _) => format!("{}\n", serde_json::to_string(value)?),
+      }))
+      .collect()
+  }
+}
+
+/// The body of the response object.
+pub enum OpenAIResponseBody {
+  /// The parsed JSON value.
+  Json(serde_json::Value),
+
+  /// The stream handle of the server-sent events.
+  EventStream(EventStream),
+}
+
+/// The response object.
+pub struct OpenAIResponse {
+  /// The body.
+  pub body: OpenAIResponseBody,
+
+  /// The sources and the masked values of the API key and the organization ID, which are set by the client.
+  pub credentials: serde_json::Map<String, serde_json::Value>,
+
+  /// The duration from sending the API request to receiving the first byte of the response.
+  pub first_byte: Duration,
+
+  /// The HTTP headers.
+  pub headers: HeaderMap,
+
+  /// The HTTP method of the API request, which is set by the client.
+  pub method: Option<Method>,
+
+  /// The API request parameters (body), which are set by the client.
+  pub parameter: Option<serde_json::Value>,
+
+  /// The original bytes of the JSON body, which are kept for the raw output.
+  pub raw: Option<Vec<u8>>,
+
+  /// The SHA-256 hash of the API request body in hexadecimal, which is set by the client.
+  pub request_hash: Option<String>,
+
+  /// The instant when the API request was sent.
+  pub started: Instant,
+
+  /// The HTTP status code.
+  pub status: StatusCode,
+
+  /// The final URL.
+  pub url: Url,
+}
+
+impl OpenAIResponse {
+  /// Resolve the response object from the HTTP response, which was sent at `start`.
+  pub async fn resolve(response: Response, start: Instant) -> Result<Self> {
+    let url = response.url().clone();
+    Self::resolve_with_url(response, url, start).await
+  }
+
+  /// Resolve the response object like [`OpenAIResponse::resolve`] from the HTTP response of the URL,
+  /// which does not record the URL itself, for example, the one converted from [`http::Response`].
+  pub async fn resolve_with_url(response: Response, url: Url, start: Instant) -> Result<Self> {
+    let first_byte = start.elapsed();
+    let status = response.status();
+    let headers = response.headers().clone();
+    let content_type: Mime = headers
+      .get(CONTENT_TYPE)
+      .ok_or(Error::msg("The API response does not contain the header `Content-Type`"))?
+      .to_str()?
+      .parse()?;
+    info!("Resolving the API response in the content type: {content_type:?}");
+
+    let mut raw = None;
+    let body = match content_type.subtype() {
+      mime::JSON => {
+        let bytes = response.bytes().await?;
+        info!("Resolved the API response: <JSON Object ({} bytes)>", bytes.len());
+        trace!("< {}", redact_text(&String::from_utf8_lossy(&bytes)));
+        trace!("Timing: total {:?}", start.elapsed());
+        let value = serde_json::from_slice(&bytes)
+          .map_err(Error::from)
+          .map_err(|err| err.context(format!("HTTP status {status} for url ({url})")))
+          .map_err(|err| err.context("Failed to resolve the API response"))?;
+        raw = Some(bytes.to_vec());
+        OpenAIResponseBody::Json(value)
+      },
+      mime::EVENT_STREAM => {
+        let events = response.bytes_stream().eventsource();
+        OpenAIResponseBody::EventStream(Box::pin(stream::unfold(
+          (Some(events), 0),
+          move |(events, size)| async move {
+            let mut events = events?;
+            let event = match events.next().await {
+              Some(event) => event,
+              None => {
+                trace!("< <Event Stream ({} bytes)>", size);
+                trace!("Timing: total {:?}", start.elapsed());
+                return None;
+              },
+            };
+            let data = match event {
+              Ok(event) if event.retry.is_some() => {
+                return Some((
+                  Err(Error::msg("Failed to resolve API response: Retry occurred")),
+                  (None, size),
+                ));
+              },
+              Ok(event) => event.data,
+              Err(err) => return Some((Err(Error::from(err)), (None, size))),
+            };
+            info!(
+              "Resolved the API response: <Event Stream Data: ({} bytes)>",
+              data.len(),
+            );
+            trace!("< {}", redact_text(&data));
+            let size = size + data.len();
+            if data == "[DONE]" {
+              info!("Reached the end of the API response");
+              trace!("< <Event Stream ({} bytes)>", size);
+              trace!("Timing: total {:?}", start.elapsed());
+              return None;
+            }
+            Some((Ok(data), (Some(events), size)))
+          },
+        )))
+      },
+      _ => return Err(Error::msg(format!(
+        "Failed to resolve API response: {content_type:?} is an invalid format"
+      ))),
+    };
+    Ok(Self {
+      body,
+      credentials: serde_json::Map::new(),
+      first_byte,
+      headers,
+      method: None,
+      parameter: None,
+      raw,
+      request_hash: None,
+      started: start,
+      status,
+      url,
+    })
+  }
+
+  /// Check the response body against the response schema of the operation on the endpoint.
+  ///
+  /// The JSON body is checked at once and the data in the server-sent events are checked one by one.
+  /// The missing required fields, the unexpected types and the undocumented fields are reported
+  /// as warnings, or as errors in the strict mode. The unsuccessful responses are skipped.
+  pub fn check(self, endpoint: &Endpoint, method: &str, mode: ResponseCheck) -> Result<Self> {
+    let Some(response) = endpoint
+      .operation(method)
+      .and_then(|operation| operation.response.as_ref())
+      .filter(|_| self.status.is_success())
+    else {
+      return Ok(self);
+    };
+    let target = format!("the response schema of {method} {}", endpoint.path);
+    let body = match self.body {
+      OpenAIResponseBody::Json(value) => {
+        check_value(&value, &response.schema, &target, mode)?;
+        OpenAIResponseBody::Json(value)
+      },
+      OpenAIResponseBody::EventStream(stream) => {
+        let schema = response.stream_schema.clone().unwrap_or_else(|| response.schema.clone());
+        OpenAIResponseBody::EventStream(Box::pin(stream.map(move |data| {
+          let data = data?;
+          match serde_json::from_str(&data) {
+            Ok(value) => check_value(&value, &schema, &target, mode)?,
+            Err(err) => report(format!("The API response data is not in JSON format: {err}"), mode)?,
+          }
+          Ok(data)
+        })))
+      },
+    };
+    Ok(Self { body, ..self })
+  }
+
+  /// Export the response body to the output writer with the default options.
+  ///
+  /// It fails if the HTTP status code is not successful.
+  pub async fn export(self, output: Output) -> Result<()> {
+    self.export_with(output, &ExportOptions::default()).await
+  }
+
+  /// Export the response body to the output writer with the options.
+  ///
+  /// The output is flushed after every chunk of the stream, and finished only if the whole body is exported.
+  /// It fails if the HTTP status code is not successful.
+  pub async fn export_with(self, output: Output, options: &ExportOptions) -> Result<()> {
+    let status_error = if self.status.is_client_error() || self.status.is_server_error() {
+      Err(Error::msg(format!("HTTP status {} for url ({})", self.status, self.url)))
+    } else {
+      Ok(())
+    };
+    let exporting_message = format!(
+      "Exporting the output to the {}",
+      if output.is_file() { "file" } else { "standard output" },
+    );
+    let mut output = output;
+    output.describe(&self, options);
+
+    match self.body {
+      OpenAIResponseBody::Json(object) => {
+        let response_json = serde_json::to_string_pretty(&object)?;
+        if let Err(status_error) = status_error {
+          return Err(
+            Error::msg("\u{1b}[F")
+              .context(format!("The API response in JSON format:\n{}", response_json))
+              .context(status_error)
+              .context("Failed to resolve the API response")
+          );
+        }
+        info!("{}", exporting_message);
+        let raw = self.raw.unwrap_or_else(|| response_json.into_bytes());
+        output.record_usage(&raw);
+        output.write_all(&options.format_json(&object, &raw)?)?;
+      },
+      OpenAIResponseBody::EventStream(mut stream) => {
+        status_error?; // should not be an error
+
+        info!("{}", exporting_message);
+        while let Some(data) = stream.next().await {
+          let data = data?;
+          output.record_usage(data.as_bytes());
+          output.write_all(options.format_data(&data)?.as_bytes())?;
+          output.flush()?;
+        }
+      },
+    }
+    output.finish()
+  }
+}
+
+fn check_value(value: &serde_json::Value, schema: &serde_json::Value, target: &str, mode: ResponseCheck) -> Result<()> {
+  let violations = Schema(schema).validate(value);
+  if violations.is_empty() {
+    trace!("The API response matches {target}");
+    return Ok(());
+  }
+  report(
+    format!(
+      "The API response does not match {target}:\n{}",
+      violations.iter().map(|violation| format!("  {violation}")).collect::<Vec<_>>().join("\n"),
+    ),
+    mode,
+  )
+}
+
+/// Warn about the mismatch of the API response, or fail with it in the strict mode.
+fn report(message: String, mode: ResponseCheck) -> Result<()> {
+  match mode {
+    ResponseCheck::Warn => {
+      warn!("{message}");
+      Ok(())
+    },
+    ResponseCheck::Strict => Err(Error::msg(message)),
+  }
+}
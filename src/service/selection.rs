@@ -0,0 +1,145 @@
+use crate::{Error, Result};
+use serde_json::Value;
+use std::{fmt, str::FromStr};
+
+/// The step of the selection query.
+#[derive(Clone, Debug, PartialEq)]
+pub enum SelectionStep {
+  /// The object field, for example, `.name` or `["name"]`.
+  Field(String),
+
+  /// The array index, which counts from the end if negative, for example, `[0]` or `[-1]`.
+  Index(i64),
+
+  /// The iteration over the array elements or the object values, for example, `[]`, `[*]` or `.*`.
+  Iterate,
+}
+
+/// The selection query on JSON values, which is a subset of jq and JSONPath,
+/// for example, `.choices[0].message.content`, `.data[].id` or `$.data[*].id`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Selector {
+  steps: Vec<SelectionStep>,
+  text: String,
+}
+
+impl Selector {
+  /// Returns the steps of the query.
+  pub fn steps(&self) -> &[SelectionStep] {
+    &self.steps
+  }
+
+  /// Select the values from the JSON value in order.
+  ///
+  /// The missing fields and indices are selected as `null`, like jq.
+  /// It fails if a step does not apply to the type of the value.
+  pub fn select<'a>(&self, value: &'a Value) -> Result<Vec<&'a Value>> {
+    static NULL: Value = Value::Null;
+    let mut values = vec![value];
+    for step in &self.steps {
+      let mut selected = vec![];
+      for value in values {
+        match (step, value) {
+          (SelectionStep::Field(_) | SelectionStep::Index(_), Value::Null) => selected.push(&NULL),
+          (SelectionStep::Field(name), Value::Object(object)) => selected.push(object.get(name).unwrap_or(&NULL)),
+          (SelectionStep::Index(index), Value::Array(array)) => {
+            let index = if *index < 0 { array.len() as i64 + index } else { *index };
+            selected.push(usize::try_from(index).ok().and_then(|index| array.get(index)).unwrap_or(&NULL));
+          },
+          (SelectionStep::Iterate, Value::Array(array)) => selected.extend(array),
+          (SelectionStep::Iterate, Value::Object(object)) => selected.extend(object.values()),
+          (step, value) => return Err(Error::msg(format!(
+            "The selection `{}` cannot apply {} to {}",
+            self.text,
+            match step {
+              SelectionStep::Field(name) => format!("the field {name:?}"),
+              SelectionStep::Index(index) => format!("the index {index}"),
+              SelectionStep::Iterate => "the iteration".to_string(),
+            },
+            type_name(value),
+          ))),
+        }
+      }
+      values = selected;
+    }
+    Ok(values)
+  }
+}
+
+impl fmt::Display for Selector {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str(&self.text)
+  }
+}
+
+impl FromStr for Selector {
+  type Err = Error;
+
+  fn from_str(text: &str) -> Result<Self> {
+    let invalid = |reason: &str| Error::msg(format!("The selection `{text}` is invalid: {reason}"));
+    let mut rest = text.trim();
+    rest = rest.strip_prefix('$').unwrap_or(rest);
+    let mut steps = vec![];
+    while !rest.is_empty() {
+      if let Some(after) = rest.strip_prefix('[') {
+        let (inner, after) = after.split_once(']').ok_or_else(|| invalid("missing `]`"))?;
+        let inner = inner.trim();
+        steps.push(match inner {
+          "" | "*" => SelectionStep::Iterate,
+          _ if inner.starts_with(['"', '\'']) => {
+            SelectionStep::Field(unquote(inner).ok_or_else(|| invalid("unclosed quote"))?)
+          },
+          _ => SelectionStep::Index(inner.parse().map_err(|_| invalid("the index should be an integer"))?),
+        });
+        rest = after;
+      } else if let Some(after) = rest.strip_prefix('.') {
+        if let Some(after) = after.strip_prefix('*') {
+          steps.push(SelectionStep::Iterate);
+          rest = after;
+        } else if let Some(after) = after.strip_prefix('"') {
+          let (name, after) = after.split_once('"').ok_or_else(|| invalid("unclosed quote"))?;
+          steps.push(SelectionStep::Field(name.to_string()));
+          rest = after;
+        } else {
+          let end = after
+            .find(|char: char| !(char.is_alphanumeric() || char == '_' || char == '-'))
+            .unwrap_or(after.len());
+          if end > 0 {
+            steps.push(SelectionStep::Field(after[..end].to_string()));
+          } else if !(after.is_empty() || after.starts_with('[')) {
+            return Err(invalid("a field name is expected after `.`"));
+          }
+          rest = &after[end..];
+        }
+      } else {
+        return Err(invalid("a step should start with `.` or `[`"));
+      }
+    }
+    Ok(Self { steps, text: text.to_string() })
+  }
+}
+
+impl TryFrom<&str> for Selector {
+  type Error = Error;
+
+  fn try_from(text: &str) -> Result<Self> {
+    Self::from_str(text)
+  }
+}
+
+fn type_name(value: &Value) -> &'static str {
+  match value {
+    Value::Null => "null",
+    Value::Bool(_) => "a boolean",
+    Value::Number(_) => "a number",
+    Value::String(_) => "a string",
+    Value::Array(_) => "an array",
+    Value::Object(_) => "an object",
+  }
+}
+
+fn unquote(text: &str) -> Option<String> {
+  let quote = text.chars().next()?;
+  let inner = text.strip_prefix(quote)?.strip_suffix(quote)?;
+  Some(inner.to_string())
+}
@@ -1,4 +1,27 @@
+#[cfg(test)]
+mod openapi {
+  mod catalog;
+  mod deprecation;
+  mod diff;
+  mod router;
+  mod schema;
+  mod suggestion;
+  mod validation;
+}
+
 #[cfg(test)]
 mod program {
+  mod command;
+  mod item;
+  mod layers;
   mod loaders;
+  mod logger;
+  mod template;
+}
+
+#[cfg(test)]
+mod service {
+  mod redaction;
+  mod response;
+  mod selection;
 }
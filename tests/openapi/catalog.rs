@@ -0,0 +1,27 @@
+use openai_client_cli::{context::*, loaders::*, traits::*};
+use anyhow::Result;
+
+#[test]
+fn infer_method() -> Result<()> {
+  let fetch = |method: Option<&str>, parameter: bool, path: &str| {
+    Method::fetch(&Context {
+      args: Arguments {
+        method: method.map(str::to_string),
+        parameter: parameter.then(|| Parameter::from(serde_json::json!({}))),
+        path: path.to_string(),
+        ..Default::default()
+      },
+      ..Context::new(Default::default()).unwrap()
+    })
+    .map(Method::value)
+  };
+
+  assert_eq!(fetch(None, true, "models")?, "GET");
+  assert_eq!(fetch(None, false, "chat/completions")?, "POST");
+  assert_eq!(fetch(None, false, "files/file-abc")?, "GET");
+  assert_eq!(fetch(None, true, "fine_tuning/jobs")?, "POST");
+  assert_eq!(fetch(None, false, "fine_tuning/jobs")?, "GET");
+  assert_eq!(fetch(Some("DELETE"), false, "files/file-abc")?, "DELETE");
+  assert!(fetch(Some("DELETE"), false, "chat/completions").is_err());
+  Ok(())
+}
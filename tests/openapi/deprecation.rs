@@ -0,0 +1,22 @@
+use openai_client_cli::openapi::*;
+
+#[test]
+fn deprecation() {
+  let catalog = Catalog::embedded();
+  let endpoint = |template| catalog.endpoint(template).unwrap();
+
+  assert!(endpoint("edits").is_deprecated());
+  assert!(endpoint("fine-tunes").is_deprecated());
+  assert!(endpoint("completions").is_legacy());
+  assert!(!endpoint("completions").is_deprecated());
+  assert!(!endpoint("chat/completions").is_deprecated());
+  assert_eq!(replacement("fine-tunes"), Some("fine_tuning/jobs"));
+  assert_eq!(legacy_replacement("completions"), Some("chat/completions"));
+
+  let model = deprecated_model("text-davinci-003").unwrap();
+  assert_eq!(model.shutdown, "2024-01-04");
+  assert_eq!(model.replacement, "gpt-3.5-turbo-instruct");
+  assert!(deprecated_model("gpt-4").is_none());
+  assert!(DEPRECATED_MODELS.windows(2).all(|pair| pair[0].name < pair[1].name));
+  assert!(DEPRECATED_MODELS.iter().all(|model| deprecated_model(model.replacement).is_none()));
+}
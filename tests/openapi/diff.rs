@@ -0,0 +1,86 @@
+use openai_client_cli::openapi::*;
+use anyhow::Result;
+use serde_json::json;
+
+#[test]
+fn diff() -> Result<()> {
+  let old = Catalog::from_openapi(&json!({
+    "paths": {
+      "/edits": {"post": {"operationId": "createEdit"}},
+      "/things": {
+        "get": {
+          "operationId": "listThings",
+          "responses": {"200": {"content": {"application/json": {"schema": {
+            "type": "object",
+            "properties": {"data": {"type": "array", "items": {
+              "type": "object",
+              "properties": {"id": {"type": "string"}, "owner": {"type": "string"}},
+            }}},
+          }}}}},
+        },
+        "post": {
+          "operationId": "createThing",
+          "requestBody": {"content": {"application/json": {"schema": {
+            "type": "object",
+            "required": ["name"],
+            "properties": {"name": {"type": "string"}, "size": {"type": "integer"}},
+          }}}},
+        },
+      },
+    },
+  }))?;
+  let new = Catalog::from_openapi(&json!({
+    "paths": {
+      "/things": {
+        "get": {
+          "operationId": "listThings",
+          "responses": {"200": {"content": {"application/json": {"schema": {
+            "type": "object",
+            "properties": {"data": {"type": "array", "items": {
+              "type": "object",
+              "properties": {"id": {"type": "string"}},
+            }}},
+          }}}}},
+        },
+        "delete": {"operationId": "deleteThings", "deprecated": true},
+        "post": {
+          "operationId": "createThing",
+          "deprecated": true,
+          "requestBody": {"content": {"application/json": {"schema": {
+            "type": "object",
+            "required": ["name", "size"],
+            "properties": {"name": {"type": "string"}, "size": {"type": "integer"}},
+          }}}},
+        },
+      },
+      "/widgets": {"get": {"operationId": "listWidgets"}},
+    },
+  }))?;
+  let diff = CatalogDiff::new(&old, &new);
+
+  assert_eq!(diff.added_paths, ["widgets"]);
+  assert_eq!(diff.removed_paths, ["edits"]);
+  assert_eq!(diff.changed_methods[0].added, ["DELETE"]);
+  assert_eq!(diff.new_required_fields[0].fields, ["size"]);
+  assert_eq!(diff.removed_response_fields[0].fields, ["data[].owner"]);
+  assert_eq!(diff.deprecated_operations.len(), 1);
+  assert_eq!(
+    diff.to_string(),
+    "\
+Added paths:
+  + widgets
+Removed paths:
+  - edits
+Changed methods:
+  ~ things: +DELETE
+New required request fields:
+  ! POST things: `size`
+Removed response fields:
+  - GET things: `data[].owner`
+Newly deprecated operations:
+  ! POST things
+",
+  );
+  assert!(CatalogDiff::new(&new, &new).is_empty());
+  Ok(())
+}
@@ -0,0 +1,38 @@
+use openai_client_cli::{loaders::*, openapi::*, traits::*};
+use std::str::FromStr;
+
+#[test]
+fn route() {
+  let router = Router::embedded();
+  let template = |path| router.route(path).map(|route| route.endpoint.path.as_str());
+
+  assert_eq!(template("chat/completions"), Some("chat/completions"));
+  assert_eq!(template("threads/runs"), Some("threads/runs"));
+  assert_eq!(template("threads/thread_abc"), Some("threads/{thread_id}"));
+  assert_eq!(template("files/file-abc/content"), Some("files/{file_id}/content"));
+  assert_eq!(template("garbage/chat/completions"), None);
+  assert_eq!(template("files//content"), None);
+  assert_eq!(
+    router.route("threads/thread_abc/runs/run_abc").unwrap().params,
+    [("thread_id".into(), "thread_abc".into()), ("run_id".into(), "run_abc".into())],
+  );
+}
+
+#[test]
+fn substitute() {
+  let params = [("file_id".to_string(), "file-abc".to_string())];
+  let path = Path::with_params("https://api.openai.com/v1/files/{file_id}/content", &params).unwrap();
+
+  assert_eq!(path.value_ref(), "files/file-abc/content");
+  assert_eq!(path.template(), "files/{file_id}/content");
+  assert_eq!(Path::from_str("/v1/models").unwrap().value_ref(), "models");
+  assert_eq!(
+    Path::from_str("threads/{thread_id}/runs/{run_id}").err().unwrap().to_string(),
+    "Missing the path parameters: `thread_id`, `run_id`",
+  );
+
+  let params = [("model".to_string(), "ft:gpt-4o/a b".to_string())];
+  let path = Path::with_params("models/{model}", &params).unwrap();
+  assert_eq!(path.value_ref(), "models/ft%3Agpt-4o%2Fa%20b");
+  assert_eq!(path.template(), "models/{model}");
+}
@@ -0,0 +1,53 @@
+use openai_client_cli::openapi::*;
+use serde_json::json;
+
+#[test]
+fn describe() {
+  let catalog = Catalog::embedded();
+  let operation = catalog
+    .endpoint("chat/completions")
+    .and_then(|endpoint| endpoint.operation("POST"))
+    .unwrap();
+  let request = operation.request.as_ref().unwrap();
+  let schema = Schema(&request.schema);
+  let required: Vec<_> = schema.fields().into_iter().filter(|f| f.required).map(|f| f.name).collect();
+
+  assert_eq!(operation.summary, "Creates a model response for the given chat conversation.");
+  assert_eq!(request.content_type, "application/json");
+  assert_eq!(required, ["messages", "model"]);
+  assert!(request.example.as_ref().is_some_and(|example| example.get("messages").is_some()));
+  assert!(catalog.endpoint("models").unwrap().operations[0].request.is_none());
+}
+
+#[test]
+fn type_name() {
+  let type_name = |schema| Schema(&schema).type_name();
+
+  assert_eq!(type_name(json!({"type": "integer", "nullable": true})), "integer | null");
+  assert_eq!(type_name(json!({"type": "array", "items": {"type": "string"}})), "array<string>");
+  assert_eq!(type_name(json!({"enum": ["url", "b64_json"]})), r#""url" | "b64_json""#);
+  assert_eq!(type_name(json!({"type": "string", "format": "binary"})), "file");
+  assert_eq!(
+    type_name(json!({"oneOf": [{"type": "string"}, {"type": "array", "items": {"type": "integer"}}]})),
+    "string | array<integer>",
+  );
+  assert_eq!(type_name(json!({"properties": {}})), "object");
+  assert_eq!(type_name(json!({})), "any");
+}
+
+#[test]
+fn placeholder() {
+  let placeholder = |schema| Schema(&schema).placeholder();
+
+  assert_eq!(placeholder(json!({"type": "integer", "default": 1})), json!(1));
+  assert_eq!(placeholder(json!({"enum": ["url", "b64_json"]})), json!("url"));
+  assert_eq!(placeholder(json!({"anyOf": [{"type": "string"}, {"type": "array"}]})), json!(""));
+  assert_eq!(
+    placeholder(json!({
+      "type": "object",
+      "properties": {"role": {"enum": ["user"]}, "name": {"type": "string"}},
+      "required": ["role"],
+    })),
+    json!({"role": "user"}),
+  );
+}
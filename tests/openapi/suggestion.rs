@@ -0,0 +1,13 @@
+use openai_client_cli::openapi::*;
+
+#[test]
+fn suggest() {
+  let catalog = Catalog::embedded();
+  let suggest = |path| catalog.suggest(path).into_iter().map(|s| s.to_string()).collect::<Vec<_>>();
+
+  assert_eq!(suggest("chat/completion")[0], "`chat/completions`");
+  assert_eq!(suggest("fine_tunes")[0], "`fine_tuning/jobs` (replacing the deprecated `fine-tunes`)");
+  assert_eq!(suggest("engines")[0], "`models` (replacing the deprecated `engines`)");
+  assert_eq!(suggest("modelz/gpt-4")[0], "`models/{model}`");
+  assert!(suggest("something/completely/different").is_empty());
+}
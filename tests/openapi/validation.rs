@@ -0,0 +1,49 @@
+use openai_client_cli::openapi::*;
+use serde_json::json;
+
+#[test]
+fn validate() {
+  let catalog = Catalog::embedded();
+  let schema = &catalog
+    .endpoint("chat/completions")
+    .and_then(|endpoint| endpoint.operation("POST"))
+    .and_then(|operation| operation.request.as_ref())
+    .unwrap()
+    .schema;
+  let validate = |value| Schema(schema).validate(&value).iter().map(ToString::to_string).collect::<Vec<_>>();
+
+  assert!(validate(json!({"model": "gpt-4", "messages": [{"role": "user", "content": "Hi"}]})).is_empty());
+  assert_eq!(
+    validate(json!({
+      "model": "gpt-4",
+      "messages": [{"role": "usr", "content": "Hi"}, {"role": "user"}],
+      "temprature": 0.5,
+      "n": "2",
+      "top_p": 3,
+    })),
+    [
+      r#"`/messages/0/role`: expected "system" | "user" | "assistant" | "tool" | "function", found "usr""#,
+      "`/messages/1`: missing the required field `content`",
+      "`/temprature`: unknown field, did you mean `temperature`?",
+      "`/n`: expected integer | null, found string",
+      "`/top_p`: expected a number not greater than 1, found 3",
+    ],
+  );
+  assert_eq!(validate(json!([])), ["`/`: expected object, found array"]);
+  assert_eq!(validate(json!({"messages": []}))[0], "`/`: missing the required field `model`");
+}
+
+#[test]
+fn validate_examples() {
+  for endpoint in &Catalog::embedded().endpoints {
+    for operation in &endpoint.operations {
+      let Some(request) = operation.request.as_ref() else {
+        continue;
+      };
+      if let Some(example) = request.example.as_ref() {
+        let violations = Schema(&request.schema).validate(example);
+        assert!(violations.is_empty(), "{} {}: {violations:?}", operation.method, endpoint.path);
+      }
+    }
+  }
+}
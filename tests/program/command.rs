@@ -0,0 +1,60 @@
+use openai_client_cli::{command::*, context::*};
+use anyhow::Result;
+use std::{collections::HashMap, sync::Arc};
+
+#[test]
+fn run() -> Result<()> {
+  let context = Context::new(Default::default())?;
+  let run = |command: Command| -> Result<String> {
+    let mut output = vec![];
+    command.run(&context, &mut output)?;
+    Ok(String::from_utf8(output)?)
+  };
+
+  let endpoints = run(Command::Endpoints { filter: Some("CHAT".to_string()) })?;
+  assert!(endpoints.starts_with("POST   chat/completions  Creates a model response"));
+  assert_eq!(endpoints.lines().count(), 1);
+  assert!(run(Command::Endpoints { filter: Some("nothing at all".to_string()) }).is_err());
+
+  let describe = run(Command::Describe { path: "https://api.openai.com/v1/chat/completions".to_string() })?;
+  assert!(describe.starts_with("POST chat/completions\n"));
+  assert!(describe.contains("    Required fields:\n      messages: array<object>\n"));
+  assert!(describe.contains("  Example body:\n    {\n"));
+
+  let describe = run(Command::Describe { path: "files/file-abc".to_string() })?;
+  assert!(describe.contains("GET files/{file_id}\n"));
+  assert!(describe.contains("DELETE files/{file_id}\n"));
+  assert!(describe.contains("  Path parameters: `file_id`\n"));
+  assert!(run(Command::Describe { path: "chat/completion".to_string() }).is_err());
+  Ok(())
+}
+
+#[test]
+fn init() -> Result<()> {
+  let fs = MemoryFileSystem::default();
+  let context = Context {
+    args: Default::default(),
+    current_dir: "/work".into(),
+    env: HashMap::new(),
+    fs: Arc::new(fs.clone()),
+    home_dir: None,
+  };
+  let init = |path: &str, force: bool| {
+    Command::Init { force, path: path.to_string() }.run(&context, &mut vec![])
+  };
+
+  init("chat/completions", false)?;
+  let parameter: serde_json::Value = serde_json::from_slice(&fs.get("/work/openai.json").unwrap())?;
+  assert_eq!(parameter["model"], "gpt-3.5-turbo");
+  assert!(parameter["messages"].is_array());
+  assert!(parameter.get("temperature").is_none());
+
+  assert!(init("embeddings", false).is_err());
+  init("embeddings", true)?;
+  let parameter: serde_json::Value = serde_json::from_slice(&fs.get("/work/openai.json").unwrap())?;
+  assert!(parameter.get("input").is_some());
+
+  assert!(init("models", true).is_err());
+  assert!(init("images/edits", true).is_err());
+  Ok(())
+}
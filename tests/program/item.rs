@@ -0,0 +1,60 @@
+use openai_client_cli::{context::*, item::*};
+use serde_json::json;
+use std::{collections::HashMap, sync::Arc};
+use anyhow::Result;
+
+#[test]
+fn parse() -> Result<()> {
+  let item: ParameterItem = "messages[0].content=@prompt.txt".parse()?;
+  assert_eq!(item.key, [
+    ItemKey::Name("messages".into()),
+    ItemKey::Index(0),
+    ItemKey::Name("content".into()),
+  ]);
+  assert_eq!(item.value, ItemValue::StringFile("prompt.txt".into()));
+  assert_eq!("temperature:=0.2".parse::<ParameterItem>()?.value, ItemValue::Json(json!(0.2)));
+  assert_eq!("stop[]=a=b".parse::<ParameterItem>()?.value, ItemValue::String("a=b".into()));
+  assert!("model".parse::<ParameterItem>().is_err());
+  assert!("[0]=x".parse::<ParameterItem>().is_err());
+  assert!("n:=nope".parse::<ParameterItem>().is_err());
+  Ok(())
+}
+
+#[test]
+fn apply() -> Result<()> {
+  let fs = MemoryFileSystem::default();
+  fs.insert("/work/prompt.txt", "Hello");
+  fs.insert("/work/tools.json", r#"[{"type": "function"}]"#);
+  let context = Context {
+    args: Default::default(),
+    current_dir: "/work".into(),
+    env: HashMap::new(),
+    fs: Arc::new(fs),
+    home_dir: None,
+  };
+  let mut value = json!({"model": "gpt-3.5-turbo", "messages": [{"role": "user", "content": "Hi"}]});
+  for item in [
+    "model=gpt-4o",
+    "temperature:=0.2",
+    "messages[0].content=@prompt.txt",
+    "messages[1].role=assistant",
+    "stop[]=END",
+    "stop[]=STOP",
+    "tools:=@tools.json",
+  ] {
+    item.parse::<ParameterItem>()?.apply(&mut value, &context)?;
+  }
+  assert_eq!(value, json!({
+    "model": "gpt-4o",
+    "messages": [{"role": "user", "content": "Hello"}, {"role": "assistant"}],
+    "temperature": 0.2,
+    "stop": ["END", "STOP"],
+    "tools": [{"type": "function"}],
+  }));
+
+  let error = |item: &str| item.parse::<ParameterItem>()?.apply(&mut value.clone(), &context);
+  assert!(error("messages[5].content=x").unwrap_err().to_string().contains("out of range at `messages`"));
+  assert!(error("model.name=x").unwrap_err().to_string().contains("expects an object at `model`"));
+  assert!(error("missing=@missing.txt").is_err());
+  Ok(())
+}
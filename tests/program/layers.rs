@@ -0,0 +1,55 @@
+use openai_client_cli::layers::*;
+use serde_json::json;
+
+#[test]
+fn merge() {
+  let mut layers = ParameterLayers::default();
+  assert!(layers.is_empty());
+  layers.merge(json!({
+    "model": "gpt-3.5-turbo",
+    "temperature": 0.7,
+    "messages": [{"role": "system", "content": "Be terse."}],
+    "stop": ["A"],
+    "metadata": {"team": "core", "task": "base"},
+  }), "base");
+  layers.merge(json!({
+    "temperature": null,
+    "messages": {"$append": [{"role": "user", "content": "Hi"}]},
+    "stop": ["B"],
+    "metadata": {"task": "summary"},
+  }), "task");
+  layers.merge(json!({"metadata": {"$replace": {"owner": "me"}}}), "override");
+
+  assert_eq!(layers.value_ref(), Some(&json!({
+    "model": "gpt-3.5-turbo",
+    "messages": [{"role": "system", "content": "Be terse."}, {"role": "user", "content": "Hi"}],
+    "stop": ["B"],
+    "metadata": {"owner": "me"},
+  })));
+  assert_eq!(layers.source("/model"), Some("base"));
+  assert_eq!(layers.source("/temperature"), None);
+  assert_eq!(layers.source("/messages/0/content"), Some("base"));
+  assert_eq!(layers.source("/messages/1/content"), Some("task"));
+  assert_eq!(layers.source("/stop/0"), Some("task"));
+  assert_eq!(layers.source("/metadata/owner"), Some("override"));
+  assert_eq!(
+    layers.fields().iter().map(|(pointer, _, source)| format!("{pointer} {source}")).collect::<Vec<_>>(),
+    [
+      "/model base",
+      "/messages/0/role base",
+      "/messages/0/content base",
+      "/messages/1/role task",
+      "/messages/1/content task",
+      "/stop/0 task",
+      "/metadata/owner override",
+    ],
+  );
+  assert_eq!(escape_pointer("a/b~c"), "a~1b~0c");
+
+  let mut layers = ParameterLayers::default();
+  layers.merge(json!({"model": "gpt-4o", "response_format": null, "metadata": {"task": null}}), "task");
+  assert_eq!(layers.value_ref(), Some(&json!({"model": "gpt-4o", "response_format": null, "metadata": {"task": null}})));
+  assert_eq!(layers.source("/response_format"), Some("task"));
+  layers.merge(json!({"response_format": null, "seed": null}), "override");
+  assert_eq!(layers.value_ref(), Some(&json!({"model": "gpt-4o", "metadata": {"task": null}})));
+}
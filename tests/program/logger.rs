@@ -0,0 +1,19 @@
+use openai_client_cli::{logger::*, redaction::*};
+
+#[test]
+fn directives() {
+  let logger = |verbosity, filter: Option<&str>| Logger {
+    file: None,
+    filter: filter.map(str::to_string),
+    format: LogFormat::Text,
+    redactor: Redactor::default(),
+    verbosity,
+  };
+
+  assert_eq!(logger(-2, None).directives(), "off");
+  assert_eq!(logger(-1, None).directives(), "ERROR");
+  assert_eq!(logger(0, None).directives(), "WARN");
+  assert_eq!(logger(1, None).directives(), "DEBUG");
+  assert_eq!(logger(2, None).directives(), "DEBUG,openai_client_cli=TRACE");
+  assert_eq!(logger(2, Some("info")).directives(), "info");
+}
@@ -0,0 +1,77 @@
+use openai_client_cli::{context::*, template::*};
+use serde_json::json;
+use std::{collections::HashMap, path::Path, sync::Arc};
+use anyhow::Result;
+
+#[test]
+fn include() -> Result<()> {
+  let fs = MemoryFileSystem::default();
+  fs.insert("/work/prompts/system.txt", "Be terse.");
+  fs.insert("/work/prompts/system.yaml", "role: system\ncontent: \"@system.txt\"\n");
+  fs.insert("/work/prompts/loop.json", r#"{"$include": "loop.json"}"#);
+  let context = Context {
+    args: Default::default(),
+    current_dir: "/work".into(),
+    env: HashMap::new(),
+    fs: Arc::new(fs),
+    home_dir: None,
+  };
+
+  let mut value = json!({
+    "messages": [{"$include": "prompts/system.yaml"}, {"role": "user", "content": "@@mention"}],
+    "suffix": "@prompts/system.txt",
+  });
+  ParameterTemplate::include(&context, &mut value, Path::new(""))?;
+  assert_eq!(value, json!({
+    "messages": [{"role": "system", "content": "Be terse."}, {"role": "user", "content": "@mention"}],
+    "suffix": "Be terse.",
+  }));
+
+  let mut value = json!({"a": [{"$include": "prompts/loop.json"}]});
+  let error = ParameterTemplate::include(&context, &mut value, Path::new("")).unwrap_err().to_string();
+  assert!(error.contains("at `/a/0` is included more than 16 levels deep"));
+  assert!(ParameterTemplate::include(&context, &mut json!("@missing.txt"), Path::new("")).is_err());
+  Ok(())
+}
+
+#[test]
+fn render() -> Result<()> {
+  let fs = MemoryFileSystem::default();
+  fs.insert("/work/vars.yaml", "model: gpt-4o\ntemperature: 0.2\n");
+  let mut context = Context {
+    args: Arguments {
+      vars: vec![("lang".into(), "Rust".into()), ("model".into(), "gpt-4".into())],
+      vars_file: Some("vars.yaml".into()),
+      ..Default::default()
+    },
+    current_dir: "/work".into(),
+    env: HashMap::from([("USER_ID".into(), "user-1".into())]),
+    fs: Arc::new(fs),
+    home_dir: None,
+  };
+
+  let template = ParameterTemplate::new(&context)?;
+  let mut value = json!({
+    "model": "{{model}}",
+    "temperature": "{{ temperature }}",
+    "user": "${USER_ID}",
+    "messages": [{"content": "Review the {{lang}} code at $${HOME} with t={{temperature}} in $${{ lang }}"}],
+  });
+  template.render(&context, &mut value)?;
+  assert_eq!(value, json!({
+    "model": "gpt-4",
+    "temperature": 0.2,
+    "user": "user-1",
+    "messages": [{"content": "Review the Rust code at ${HOME} with t=0.2 in {{ lang }}"}],
+  }));
+
+  let error = template.render(&context, &mut json!({"messages": [{"content": "{{nope}}"}]}));
+  assert_eq!(
+    error.unwrap_err().to_string(),
+    "The variable `nope` is undefined at `/messages/0/content` of the API request parameters",
+  );
+  context.env.clear();
+  let error = template.render(&context, &mut json!({"user": "${USER_ID}"}));
+  assert!(error.unwrap_err().to_string().contains("environment variable `USER_ID` is undefined at `/user`"));
+  Ok(())
+}
@@ -0,0 +1,214 @@
+use openai_client_cli::{context::*, loaders::*, openapi::*, response::*, traits::*, Result};
+use futures_util::StreamExt;
+use serde_json::json;
+use std::{collections::HashMap, sync::Arc, time::Instant};
+
+fn response(content_type: &str, body: &'static str) -> reqwest::Response {
+  http::Response::builder()
+    .header("content-type", content_type)
+    .body(body)
+    .unwrap()
+    .into()
+}
+
+#[tokio::test]
+async fn resolve() -> Result<()> {
+  let json = response("application/json", r#"{"object": "list", "data": []}"#);
+  let json = OpenAIResponse::resolve(json, Instant::now()).await?;
+  assert!(json.status.is_success());
+  assert_eq!(json.raw.as_deref(), Some(br#"{"object": "list", "data": []}"#.as_slice()));
+  match json.body {
+    OpenAIResponseBody::Json(object) => assert_eq!(object["object"], "list"),
+    _ => panic!("The body should be JSON"),
+  }
+
+  let stream = response("text/event-stream", "data: {\"id\":1}\n\ndata: {\"id\":2}\n\ndata: [DONE]\n\n");
+  let stream = OpenAIResponse::resolve(stream, Instant::now()).await?;
+  match stream.body {
+    OpenAIResponseBody::EventStream(stream) => assert_eq!(
+      stream.map(Result::unwrap).collect::<Vec<_>>().await,
+      vec![r#"{"id":1}"#, r#"{"id":2}"#],
+    ),
+    _ => panic!("The body should be an event stream"),
+  }
+  Ok(())
+}
+
+#[tokio::test]
+async fn check() -> Result<()> {
+  let catalog = Catalog::embedded();
+  let check = |content_type, body, mode| async move {
+    OpenAIResponse::resolve(response(content_type, body), Instant::now())
+      .await?
+      .check(catalog.endpoint("models").unwrap(), "GET", mode)
+  };
+
+  let valid = r#"{"object": "list", "data": [{"id": "gpt-4", "object": "model", "created": 1, "owned_by": "openai"}]}"#;
+  assert!(check("application/json", valid, ResponseCheck::Strict).await.is_ok());
+  let drifted = r#"{"object": "list", "data": [{"id": 4, "object": "model", "created": 1}], "extra": true}"#;
+  assert!(check("application/json", drifted, ResponseCheck::Warn).await.is_ok());
+  let error = check("application/json", drifted, ResponseCheck::Strict).await.err().unwrap().to_string();
+  assert!(error.contains("`/data/0`: missing the required field `owned_by`"));
+  assert!(error.contains("`/data/0/id`: expected string, found integer"));
+  assert!(error.contains("`/extra`: unknown field"));
+
+  let stream = OpenAIResponse::resolve(
+    response("text/event-stream", "data: {\"id\":\"chatcmpl-1\"}\n\ndata: [DONE]\n\n"),
+    Instant::now(),
+  )
+  .await?
+  .check(catalog.endpoint("chat/completions").unwrap(), "POST", ResponseCheck::Strict)?;
+  match stream.body {
+    OpenAIResponseBody::EventStream(mut stream) => {
+      let error = stream.next().await.unwrap().err().unwrap().to_string();
+      assert!(error.contains("the response schema of POST chat/completions"));
+      assert!(error.contains("missing the required field `choices`"));
+    },
+    _ => panic!("The body should be an event stream"),
+  }
+
+  let check_stream = |mode| async move {
+    let response = OpenAIResponse::resolve(response("text/event-stream", "data: not json\n\n"), Instant::now())
+      .await?
+      .check(catalog.endpoint("chat/completions").unwrap(), "POST", mode)?;
+    match response.body {
+      OpenAIResponseBody::EventStream(mut stream) => stream.next().await.unwrap(),
+      _ => panic!("The body should be an event stream"),
+    }
+  };
+  assert_eq!(check_stream(ResponseCheck::Warn).await?, "not json");
+  let error = check_stream(ResponseCheck::Strict).await.err().unwrap().to_string();
+  assert!(error.contains("The API response data is not in JSON format"));
+  Ok(())
+}
+
+#[tokio::test]
+async fn export_dir() -> Result<()> {
+  let fs = MemoryFileSystem::default();
+  let context = Context {
+    args: Arguments {
+      output_dir: Some("out".into()),
+      output_name: Some("{path}-{request_id}-{method}.{ext}".into()),
+      ..Default::default()
+    },
+    current_dir: "/work".into(),
+    env: HashMap::new(),
+    fs: Arc::new(fs.clone()),
+    home_dir: None,
+  };
+  let export = |request_id: &'static str, format: OutputFormat| {
+    let context = context.clone();
+    async move {
+      let response = http::Response::builder()
+        .header("content-type", "application/json")
+        .header("x-request-id", request_id)
+        .body(r#"{"id": "a"}"#)?;
+      let mut response = OpenAIResponse::resolve(response.into(), Instant::now()).await?;
+      response.method = Some(http::Method::POST);
+      response.parameter = Some(json!({"model": "gpt-4"}));
+      response.url = "https://api.openai.com/v1/chat/completions".parse()?;
+      let options = ExportOptions { format, ..Default::default() };
+      response.export_with(Output::fetch(&context)?, &options).await
+    }
+  };
+  export("req_1", OutputFormat::Compact).await?;
+  export("req_2", OutputFormat::Compact).await?;
+  export("req_1", OutputFormat::Compact).await?;
+  export("req_3", OutputFormat::Yaml).await?;
+
+  assert_eq!(fs.get("/work/out/chat-completions-req_1-post.json").unwrap(), b"{\"id\":\"a\"}\n");
+  assert!(fs.get("/work/out/chat-completions-req_2-post.json").is_some());
+  let index = String::from_utf8(fs.get("/work/out/index.jsonl").unwrap())?;
+  let index: Vec<serde_json::Value> = index.lines().map(serde_json::from_str).collect::<Result<_, _>>()?;
+  assert_eq!(index.len(), 4);
+  assert_eq!(index[0]["file"], "chat-completions-req_1-post.json");
+  assert_eq!(index[1]["request_id"], "req_2");
+  assert_eq!(index[1]["parameter"], json!({"model": "gpt-4"}));
+  assert_eq!(index[1]["status"], 200);
+  assert_eq!(index[2]["file"], "chat-completions-req_1-post-1.json");
+  assert!(fs.get("/work/out/chat-completions-req_1-post-1.json").is_some());
+  assert_eq!(index[3]["file"], "chat-completions-req_3-post.yaml");
+  assert_eq!(fs.get("/work/out/chat-completions-req_3-post.yaml").unwrap(), b"id: a\n");
+  Ok(())
+}
+
+#[tokio::test]
+async fn export_order() -> Result<()> {
+  let fs = MemoryFileSystem::default();
+  let context = Context {
+    args: Arguments { output_file: Some("out.json".into()), ..Default::default() },
+    current_dir: "/work".into(),
+    env: HashMap::new(),
+    fs: Arc::new(fs.clone()),
+    home_dir: None,
+  };
+  let body = r#"{"object": "list", "data": [{"owned_by": "openai", "id": "gpt-4"}], "has_more": false}"#;
+  let response = OpenAIResponse::resolve(response("application/json", body), Instant::now()).await?;
+  let options = ExportOptions { format: OutputFormat::Compact, ..Default::default() };
+  response.export_with(Output::fetch(&context)?, &options).await?;
+
+  assert_eq!(
+    fs.get("/work/out.json").unwrap(),
+    br#"{"object":"list","data":[{"owned_by":"openai","id":"gpt-4"}],"has_more":false}
+"#,
+  );
+  Ok(())
+}
+
+#[tokio::test]
+async fn export_meta() -> Result<()> {
+  let fs = MemoryFileSystem::default();
+  let context = Context {
+    args: Arguments {
+      output_file: Some("out.jsonl".into()),
+      save_meta: true,
+      ..Default::default()
+    },
+    current_dir: "/work".into(),
+    env: HashMap::new(),
+    fs: Arc::new(fs.clone()),
+    home_dir: None,
+  };
+  let response = http::Response::builder()
+    .header("content-type", "text/event-stream")
+    .header("x-request-id", "req_1")
+    .body("data: {\"id\":1,\"usage\":null}\n\ndata: {\"id\":2,\"usage\":{\"total_tokens\":3}}\n\ndata: [DONE]\n\n")?;
+  let mut response = OpenAIResponse::resolve(response.into(), Instant::now()).await?;
+  response.method = Some(http::Method::POST);
+  response.request_hash = Some("0123abcd".into());
+  response.export(Output::fetch(&context)?).await?;
+
+  assert!(fs.get("/work/out.jsonl").is_some());
+  let meta: serde_json::Value = serde_json::from_slice(&fs.get("/work/out.jsonl.meta.json").unwrap())?;
+  assert_eq!(meta["method"], "POST");
+  assert_eq!(meta["status"], 200);
+  assert_eq!(meta["request_id"], "req_1");
+  assert_eq!(meta["request_body_sha256"], "0123abcd");
+  assert_eq!(meta["headers"]["content-type"], "text/event-stream");
+  assert_eq!(meta["usage"], json!({"total_tokens": 3}));
+  assert!(meta["timings"]["total_ms"].as_u64().unwrap() >= meta["timings"]["first_byte_ms"].as_u64().unwrap());
+  Ok(())
+}
+
+#[test]
+fn format() -> Result<()> {
+  let text = r#"{"object":"list",  "data":[{"id":"a"},{"id":"b"}]}"#;
+  let value: serde_json::Value = serde_json::from_str(text)?;
+  let format = |format: OutputFormat, value: &serde_json::Value| -> Result<String> {
+    Ok(String::from_utf8(format.format_json(value, text.as_bytes())?)?)
+  };
+  assert_eq!(format(OutputFormat::Raw, &value)?, text);
+  assert_eq!(format(OutputFormat::Compact, &value)?, "{\"object\":\"list\",\"data\":[{\"id\":\"a\"},{\"id\":\"b\"}]}\n");
+  assert_eq!(format(OutputFormat::Ndjson, &value)?, "{\"id\":\"a\"}\n{\"id\":\"b\"}\n");
+  assert_eq!(format(OutputFormat::Ndjson, &value["data"][0])?, "{\"id\":\"a\"}\n");
+  assert_eq!(format(OutputFormat::Yaml, &value)?, "object: list\ndata:\n- id: a\n- id: b\n");
+  assert!(format(OutputFormat::Pretty, &value)?.starts_with("{\n  \"object\": \"list\",\n"));
+
+  let data = r#"{"id": 1}"#;
+  assert_eq!(OutputFormat::Raw.format_data(data)?, "{\"id\": 1}\n");
+  assert_eq!(OutputFormat::Compact.format_data(data)?, "{\"id\":1}\n");
+  assert_eq!(OutputFormat::Pretty.format_data(r#"{"id": 1, "choices": [{"index": 0}]}"#)?, "{\"id\":1,\"choices\":[{\"index\":0}]}\n");
+  assert_eq!(OutputFormat::Yaml.format_data(data)?, "---\nid: 1\n");
+  assert_eq!(OutputFormat::Pretty.format_data("not json")?, "not json\n");
+  Ok(())
+}
@@ -0,0 +1,52 @@
+use openai_client_cli::{response::*, selection::*, Result};
+use serde_json::json;
+
+#[test]
+fn select() -> Result<()> {
+  let value = json!({
+    "choices": [{"message": {"content": "Hi"}}, {"message": {"content": "Yo"}}],
+    "data": [{"id": "a"}, {"id": "b"}],
+    "usage": {"total tokens": 3},
+  });
+  let select = |text: &str| -> Result<Vec<serde_json::Value>> {
+    Ok(text.parse::<Selector>()?.select(&value)?.into_iter().cloned().collect())
+  };
+
+  assert_eq!(select(".choices[0].message.content")?, [json!("Hi")]);
+  assert_eq!(select(".choices[-1].message.content")?, [json!("Yo")]);
+  assert_eq!(select(".data[].id")?, [json!("a"), json!("b")]);
+  assert_eq!(select("$.data[*].id")?, [json!("a"), json!("b")]);
+  assert_eq!(select(r#".usage."total tokens""#)?, [json!(3)]);
+  assert_eq!(select(r#".usage["total tokens"]"#)?, [json!(3)]);
+  assert_eq!(select(".usage.*")?, [json!(3)]);
+  assert_eq!(select(".")?, [value.clone()]);
+  assert_eq!(select(".missing.field")?, [json!(null)]);
+  assert!(select(".choices.message").is_err());
+  assert!(".choices[x]".parse::<Selector>().is_err());
+  assert!("choices".parse::<Selector>().is_err());
+  Ok(())
+}
+
+#[test]
+fn export_options() -> Result<()> {
+  let options = ExportOptions {
+    format: OutputFormat::Compact,
+    raw_output: true,
+    select: Some(".choices[0].delta.content".parse()?),
+  };
+  assert_eq!(options.format_data(r#"{"choices": [{"delta": {"content": "Hi"}}]}"#)?, "Hi\n");
+  assert_eq!(options.format_data(r#"{"choices": [{"delta": {}}]}"#)?, "null\n");
+  assert!(options.format_data("not json").is_err());
+  assert_eq!(options.extension(), Some("txt"));
+
+  let options = ExportOptions { raw_output: false, select: Some(".data[]".parse()?), ..options };
+  let text = r#"{"data": [{"id": "a"}, "b"]}"#;
+  assert_eq!(options.format_json(&serde_json::from_str(text)?, text.as_bytes())?, b"{\"id\":\"a\"}\n\"b\"\n");
+
+  let options = ExportOptions { format: OutputFormat::Pretty, select: Some(".choices[0]".parse()?), ..options };
+  assert_eq!(options.format_data(r#"{"choices": [{"index": 0}]}"#)?, "{\"index\":0}\n");
+  assert!(options.format_json(&serde_json::json!({"choices": [{"index": 0}]}), b"")?.starts_with(b"{\n"));
+  assert_eq!(options.extension(), None);
+  assert_eq!(ExportOptions { format: OutputFormat::Ndjson, ..options }.extension(), Some("jsonl"));
+  Ok(())
+}